@@ -13,10 +13,10 @@ mod structure_elements;
 // Biguint: 2 bytes for the length of the big integer + n bytes (specified just before) for the actual big integer
 // Managed address: 2 bytes for the length of the big integer + n bytes (specified just before) for the actual big integer
 
-use structure_elements::{KeyElement,MarketplaceElement};
+use structure_elements::{best_discount,AttributeMatch,AuctionElement,BidElement,FeeElement,KeyElement,MarketplaceElement,NegotiationEntry,NegotiationStatus,OfferStatus,ReferrerCapElement,ReferrerElement,RingElement,RoyaltyElement,SealedAuctionElement,SealedBid,SettlementElement};
 
-// FEE for creating and taking offers
-const M_FEE: u64 = 0;
+// Royalties and fees are expressed in basis points (hundredths of a percent)
+const M_BASIS_POINTS_DENOMINATOR: u64 = 10_000;
 
 #[multiversx_sc::contract]
 pub trait BarterSwap {    
@@ -32,145 +32,2051 @@ pub trait BarterSwap {
     *-------------------------------------------------------------------------*/
     // Callable functions
     /*-------------------------------------------------------------------------*
-    * List a certain amount of a token with a unique buyer address. Payable in *
-    * any token (ESDT, NFT, SFT).                                              *
+    * List a basket of tokens with a unique buyer address. Payable in any      *
+    * token (ESDT, NFT, SFT), one or several of them in a single transfer.     *
     * Input:                                                                   *
-    * The offer id (u64) agreed by the bidder and taker.                       *
-    * Price of the offer in EGLD.                                              *
-    * Address of the taker of the offer.                                       *
+    * Price of the offer, in the payment token.                                *
+    * Address of the taker of the offer (zero address for an open offer).      *
+    * Optional payment token identifier and nonce. EGLD if not provided.       *
+    * Optional expiry timestamp. The offer never expires if not provided.      *
+    * Optional unit price flag. If set, the price is per unit of the (single)  *
+    * token listed, and the offer can be partially filled.                     *
+    * If a listing fee is set, it must be sent as the last token of the        *
+    * transfer, and it is kept by the contract with the other fees.            *
+    * Output:                                                                  *
+    * u64 representing the ID assigned to the offer.                           *
     *-------------------------------------------------------------------------*/
     #[payable("*")]
     #[endpoint(createOffer)]
-    fn create_offer(&self, p_offer_id: u64, p_price: BigUint, p_taker_address: ManagedAddress)
+    fn create_offer(&self, p_price: BigUint, p_taker_address: ManagedAddress, p_payment_token: OptionalValue<MultiValue2<EgldOrEsdtTokenIdentifier, u64>>, p_expires_at: OptionalValue<u64>, p_unit_price: OptionalValue<bool>) -> u64
     {
-        // Get received token. Signals an error if no transfer of ESDT/NFT/SFT has been done ("incorrect number of ESDT transfers")
-        let t_esdt_structure: EsdtTokenPayment = self.call_value().single_esdt();
+        // Get received tokens, minus the listing fee. All of them are escrowed together as a single bundle
+        let t_esdt_payments: ManagedVec<EsdtTokenPayment> = self.take_listing_fee();
+        require!(!t_esdt_payments.is_empty(), "No tokens provided for the offer.");
 
         // Get bidder address (the bidder is the caller)
         let t_bidder_address: ManagedAddress = self.blockchain().get_caller();
 
+        // Get the token the offer is priced in. Defaults to EGLD
+        let (t_payment_token, t_payment_nonce) = match p_payment_token {
+            OptionalValue::Some(t_payment_token) => t_payment_token.into_tuple(),
+            OptionalValue::None => (EgldOrEsdtTokenIdentifier::egld(), 0u64),
+        };
+        require!(t_payment_token.is_valid(), "Invalid payment token provided.");
+
+        // Unit price offers can only list a single token, to know what the price refers to
+        let t_unit_price: bool = p_unit_price.into_option().unwrap_or_default();
+        require!(!t_unit_price || t_esdt_payments.len() == 1, "Unit price offers must list a single token.");
+
         // Insert new element into the map
-        self.insert_element(t_esdt_structure, p_price, p_offer_id, t_bidder_address, p_taker_address);
+        self.insert_element(t_esdt_payments, ManagedVec::new(), t_payment_token, t_payment_nonce, p_price, t_unit_price, p_expires_at.into_option().unwrap_or_default(), t_bidder_address, p_taker_address)
+    }
+    /*-------------------------------------------------------------------------*
+    * List a basket of tokens in exchange of other tokens (barter). Payable in *
+    * any token (ESDT, NFT, SFT), one or several of them.                      *
+    * Input:                                                                   *
+    * Address of the taker of the offer (zero address for an open offer).      *
+    * List of tokens (identifier, nonce, amount) the bidder wants in exchange. *
+    * Optional expiry timestamp. The offer never expires if not provided.      *
+    * If a listing fee is set, it must be sent as the last token of the        *
+    * transfer, as for createOffer.                                            *
+    * Output:                                                                  *
+    * u64 representing the ID assigned to the offer.                           *
+    *-------------------------------------------------------------------------*/
+    #[payable("*")]
+    #[endpoint(createBarterOffer)]
+    fn create_barter_offer(&self, p_taker_address: ManagedAddress, p_wanted_tokens: ManagedVec<EsdtTokenPayment>, p_expires_at: OptionalValue<u64>) -> u64
+    {
+        // Get received tokens, minus the listing fee. All of them are escrowed together as a single bundle
+        let t_esdt_payments: ManagedVec<EsdtTokenPayment> = self.take_listing_fee();
+        require!(!t_esdt_payments.is_empty(), "No tokens provided for the offer.");
+
+        // Check the tokens asked in exchange
+        for t_wanted_token in p_wanted_tokens.iter() {
+            require!(t_wanted_token.token_identifier.is_valid_esdt_identifier() && t_wanted_token.amount > 0u32, "Invalid wanted token provided.");
+        }
+        require!(!p_wanted_tokens.is_empty(), "No wanted tokens provided for the offer.");
+
+        // Get bidder address (the bidder is the caller)
+        let t_bidder_address: ManagedAddress = self.blockchain().get_caller();
+
+        // Insert new element into the map. A barter offer has no price
+        self.insert_element(t_esdt_payments, p_wanted_tokens, EgldOrEsdtTokenIdentifier::egld(), 0u64, BigUint::zero(), false, p_expires_at.into_option().unwrap_or_default(), t_bidder_address, p_taker_address)
+    }
+    /*-------------------------------------------------------------------------*
+    * List a basket of tokens in a Dutch auction: the price falls linearly     *
+    * from a start price to a floor price between two timestamps, and stays at *
+    * the floor price afterwards. Payable in any token (ESDT, NFT, SFT), one   *
+    * or several of them.                                                      *
+    * Input:                                                                   *
+    * Address of the taker of the offer (zero address for an open offer).      *
+    * Start price, in the payment token.                                       *
+    * Floor price, in the payment token.                                       *
+    * Timestamp at which the price starts to fall.                             *
+    * Timestamp at which the floor price is reached.                           *
+    * Optional payment token identifier and nonce. EGLD if not provided.       *
+    * Optional expiry timestamp. The offer never expires if not provided.      *
+    * If a listing fee is set, it must be sent as the last token of the        *
+    * transfer, as for createOffer.                                            *
+    * Output:                                                                  *
+    * u64 representing the ID assigned to the offer.                           *
+    *-------------------------------------------------------------------------*/
+    #[payable("*")]
+    #[endpoint(createDutchOffer)]
+    fn create_dutch_offer(&self, p_taker_address: ManagedAddress, p_start_price: BigUint, p_floor_price: BigUint, p_decline_start: u64, p_decline_end: u64, p_payment_token: OptionalValue<MultiValue2<EgldOrEsdtTokenIdentifier, u64>>, p_expires_at: OptionalValue<u64>) -> u64
+    {
+        // Get received tokens, minus the listing fee. All of them are escrowed together as a single bundle
+        let t_esdt_payments: ManagedVec<EsdtTokenPayment> = self.take_listing_fee();
+        require!(!t_esdt_payments.is_empty(), "No tokens provided for the offer.");
+        require!(p_start_price > p_floor_price, "Start price must be above the floor price.");
+        require!(p_decline_start < p_decline_end, "Invalid price decline period.");
+
+        // Get bidder address (the bidder is the caller)
+        let t_bidder_address: ManagedAddress = self.blockchain().get_caller();
+
+        // Get the token the offer is priced in. Defaults to EGLD
+        let (t_payment_token, t_payment_nonce) = match p_payment_token {
+            OptionalValue::Some(t_payment_token) => t_payment_token.into_tuple(),
+            OptionalValue::None => (EgldOrEsdtTokenIdentifier::egld(), 0u64),
+        };
+        require!(t_payment_token.is_valid(), "Invalid payment token provided.");
+
+        // Create the element with its price schedule and insert it into the map
+        let mut t_new_element = MarketplaceElement::new(t_esdt_payments, ManagedVec::new(), t_payment_token, t_payment_nonce, p_start_price, false, p_expires_at.into_option().unwrap_or_default());
+        t_new_element.set_price_decline(p_floor_price, p_decline_start, p_decline_end);
+        self.insert_new_element(t_new_element, t_bidder_address, p_taker_address)
     }
     /*-------------------------------------------------------------------------*
     * Refund an offer to the bidder. Should be called by the bidder.           *
     * Input:                                                                   *
     * u64 representing the ID.                                                 *
-    * Address of the taker of the offer.                                       *
     *-------------------------------------------------------------------------*/
     #[endpoint(refundOffer)]
-    fn refund_offer(&self, p_offer_id: u64, p_taker_address: ManagedAddress)
+    fn refund_offer(&self, p_offer_id: u64)
     {
-        // Get caller address
-        let t_bidder_address: ManagedAddress = self.blockchain().get_caller();
+        // Search for an offer of the caller
+        let t_key: KeyElement<Self::Api> = self.get_bidder_key(p_offer_id, "Refund offer not found.");
 
+        // Send the whole bundle back to the bidder
+        let t_marketplace_element: MarketplaceElement<Self::Api> = self.remove_element(&t_key);
+        self.send().direct_multi(t_key.get_bidder_address(), t_marketplace_element.get_items());
+    }
+    /*-------------------------------------------------------------------------*
+    * Send an expired offer back to its bidder. Can be called by anyone once   *
+    * the expiry timestamp of the offer has passed.                            *
+    * Input:                                                                   *
+    * u64 representing the ID.                                                 *
+    *-------------------------------------------------------------------------*/
+    #[endpoint(reclaimExpiredOffer)]
+    fn reclaim_expired_offer(&self, p_offer_id: u64)
+    {
         // Search for the element
-        match self.remove_element_by_key(p_offer_id.clone(), t_bidder_address.clone(), p_taker_address.clone()) {
-            Some(t_marketplace_element) => {
-                // Send the esdt token to the bidder
-                self.send().direct_esdt(&t_bidder_address, &t_marketplace_element.get_collection_id(), t_marketplace_element.get_nonce(), &t_marketplace_element.get_amount()); 
-            }
-            None => {
-                // Handle the case when the Option is empty
-                sc_panic!("Refund offer not found.");
-            }
-        }
+        let t_key: KeyElement<Self::Api> = match self.get_offer_key(p_offer_id) {
+            Some(t_key) => t_key,
+            None => sc_panic!("Reclaim offer not found."),
+        };
+
+        let t_marketplace_element: MarketplaceElement<Self::Api> = self.remove_element(&t_key);
+        require!(t_marketplace_element.is_expired(self.blockchain().get_block_timestamp()), "Offer has not expired.");
+        // Send the whole bundle back to the bidder
+        self.send().direct_multi(t_key.get_bidder_address(), t_marketplace_element.get_items());
     }
     /*-------------------------------------------------------------------------*
-    * Take an offer by ID. Should be called by the taker of the offer.         *
+    * Take an offer by ID. Should be called by the taker of the offer, paying  *
+    * exactly the price (plus the fee, if the buyer pays it) in the token the  *
+    * offer is priced in, or sending exactly the wanted tokens, in order, if   *
+    * it is a barter offer.                                                    *
+    * Open offers (no taker set) can be taken by anyone but the bidder.        *
+    * Expired offers cannot be taken.                                          *
+    * Unit price offers can be partially filled: the taker pays the unit price *
+    * times the quantity bought, and the rest stays listed.                    *
+    * Dutch auction offers are paid at their current price. As it keeps        *
+    * falling, they may be overpaid: the difference is refunded to the taker.  *
+    * Takers holding the discount token get a discount on the fee.             *
+    * The creators of the NFTs and SFTs sold receive their royalties out of    *
+    * the price, the bidder receives the rest (minus the fee, if the seller    *
+    * pays it). In barter offers, the fungible tokens of each side pay the     *
+    * royalties of the NFTs and SFTs of the other side.                        *
     * Input:                                                                   *
     * u64 representing the ID.                                                 *
-    * ManagedAddress representing the address of bidder.                       *
+    * Optional quantity to buy (unit price offers only). 0 or not provided     *
+    * means everything that is listed.                                         *
+    * Optional price the taker expects to pay for the tokens bought (unit      *
+    * price times quantity, or current price of a Dutch auction; fee           *
+    * excluded). If provided, the take fails if the price is above it, e.g.    *
+    * because the bidder raised it in the meantime.                            *
+    * Optional address of the approved referrer (marketplace, wallet) through  *
+    * which the offer is taken. It receives its share of the fee, up to the    *
+    * cap of its lifetime earnings in the payment token.                       *
+    * Output:                                                                  *
+    * SettlementElement representing the split of the payment (all zero for    *
+    * barter offers).                                                          *
     *-------------------------------------------------------------------------*/
-    #[payable("EGLD")]
+    #[payable("*")]
     #[endpoint(takeOffer)]
-    fn take_offer(&self, p_offer_id: u64, p_bidder_address: ManagedAddress)
+    fn take_offer(&self, p_offer_id: u64, p_quantity: OptionalValue<BigUint>, p_expected_price: OptionalValue<BigUint>, p_referrer_address: OptionalValue<ManagedAddress>) -> SettlementElement<Self::Api>
     {
         // Get caller address
         let t_taker_address: ManagedAddress = self.blockchain().get_caller();
 
-        // Remove the element from the MapMapper and perform the transactions 
-        match self.remove_element_by_key(p_offer_id.clone(), p_bidder_address.clone(), t_taker_address.clone()) {
-            Some(t_removed_marketplace_element) => {
-                require!((*self.call_value().egld_value()).eq(&(t_removed_marketplace_element.get_price().add(&BigUint::from(M_FEE)))), "Incorrect payment provided.");
-                // Send the esdt token to the taker
-                self.send().direct_esdt(&t_taker_address, &t_removed_marketplace_element.get_collection_id(), t_removed_marketplace_element.get_nonce(), &t_removed_marketplace_element.get_amount());
-                // Send the EGLD to the bidder
-                self.send().direct_egld(&p_bidder_address, &self.call_value().egld_value());
-                // Send the fee to the contract deployer
-                self.send().direct_egld(&self.blockchain().get_owner_address(),&BigUint::from(M_FEE));
+        // Only approved referrers get a share of the fee
+        let t_referrer_address: Option<ManagedAddress> = p_referrer_address.into_option();
+        if let Some(t_referrer_address) = &t_referrer_address {
+            require!(self.referrers().contains_key(t_referrer_address), "Referrer not approved.");
+            require!(*t_referrer_address != t_taker_address, "Taker cannot be its own referrer.");
+        }
+
+        // Look for an offer made to the caller, or open to anyone
+        let t_key: KeyElement<Self::Api> = match self.find_take_key(p_offer_id, &t_taker_address) {
+            Some(t_key) => t_key,
+            None => sc_panic!("Take offer not found."),
+        };
+        let t_bidder_address: ManagedAddress = t_key.get_bidder_address().clone();
+        let mut t_marketplace_element: MarketplaceElement<Self::Api> = self.marketplace_elements().remove(&t_key).unwrap();
+
+        // Expired offers can only be refunded or reclaimed
+        require!(!t_marketplace_element.is_expired(self.blockchain().get_block_timestamp()), "Offer has expired.");
+
+        // Quantity to buy. Zero means everything
+        let t_quantity: BigUint = p_quantity.into_option().unwrap_or_default();
+
+        // Perform the transactions
+        if t_marketplace_element.is_barter() {
+            require!(t_quantity == 0u32, "Quantity can only be set for unit price offers.");
+            // Get the tokens sent by the taker and verify they are the ones wanted by the bidder
+            let t_payments: ManagedVec<EsdtTokenPayment> = self.call_value().all_esdt_transfers().clone_value();
+            require!(t_payments == *t_marketplace_element.get_wanted_tokens(), "Incorrect payment provided.");
+            // Fungible tokens swapped for NFTs or SFTs are their price: pay the creators their royalties out of them
+            let t_taker_items: ManagedVec<EsdtTokenPayment> = self.pay_barter_royalties(t_marketplace_element.get_items(), &t_payments);
+            let t_bidder_items: ManagedVec<EsdtTokenPayment> = self.pay_barter_royalties(&t_payments, t_marketplace_element.get_items());
+            // Swap both bundles
+            if !t_taker_items.is_empty() {
+                self.send().direct_multi(&t_taker_address, &t_taker_items);
+            }
+            if !t_bidder_items.is_empty() {
+                self.send().direct_multi(&t_bidder_address, &t_bidder_items);
             }
-            None => {
-                // Handle the case when the Option is empty
-                sc_panic!("Take offer not found.");
+            self.clear_offer(&t_key);
+            return SettlementElement::new(BigUint::zero(), BigUint::zero(), BigUint::zero(), BigUint::zero(), BigUint::zero());
+        }
+
+        // Get the tokens bought and their price. Unit price offers may be partially filled
+        let (t_bought_items, t_price): (ManagedVec<EsdtTokenPayment>, BigUint) = if t_marketplace_element.is_unit_price() {
+            let t_listed_item: EsdtTokenPayment = t_marketplace_element.get_items().get(0);
+            let t_quantity: BigUint = if t_quantity == 0u32 { t_listed_item.amount.clone() } else { t_quantity };
+            require!(t_quantity <= t_listed_item.amount, "Invalid quantity provided.");
+            let t_price: BigUint = t_marketplace_element.get_price() * &t_quantity;
+            let t_bought_item = EsdtTokenPayment::new(t_listed_item.token_identifier, t_listed_item.token_nonce, t_quantity);
+            (ManagedVec::from_single_item(t_bought_item), t_price)
+        } else {
+            require!(t_quantity == 0u32, "Quantity can only be set for unit price offers.");
+            (t_marketplace_element.get_items().clone(), t_marketplace_element.get_current_price(self.blockchain().get_block_timestamp()))
+        };
+
+        // Protect the taker against a price update racing with the take
+        if let OptionalValue::Some(t_expected_price) = p_expected_price {
+            require!(t_price <= t_expected_price, "Offer price has changed.");
+        }
+
+        // Get the payment (EGLD or a single ESDT) and verify it matches the price of the offer.
+        // The price of a Dutch auction keeps falling, so it may be overpaid and the difference is refunded
+        let t_payment: EgldOrEsdtTokenPayment = self.call_value().egld_or_single_esdt();
+        let t_fee_config: FeeElement = self.get_fee_config();
+        let mut t_fee: BigUint = self.compute_fee(&t_fee_config, t_marketplace_element.get_payment_token(), &t_price);
+        // Holders of the discount token get a discount on the fee
+        let t_discount: u64 = self.compute_fee_discount(&t_taker_address);
+        t_fee -= &t_fee * t_discount / M_BASIS_POINTS_DENOMINATOR;
+        let t_paid: BigUint = if t_fee_config.is_paid_by_seller() { t_price.clone() } else { &t_price + &t_fee };
+        require!(t_payment.token_identifier == *t_marketplace_element.get_payment_token() &&
+                 t_payment.token_nonce == t_marketplace_element.get_payment_nonce() &&
+                 (t_payment.amount == t_paid || (t_marketplace_element.is_dutch_auction() && t_payment.amount > t_paid)), "Incorrect payment provided.");
+        let t_refund: BigUint = &t_payment.amount - &t_paid;
+
+        // Keep the rest of a partially filled offer listed under the same key
+        if t_marketplace_element.is_unit_price() {
+            t_marketplace_element.sub_amount(&t_bought_items.get(0).amount);
+        }
+        if t_marketplace_element.is_unit_price() && t_marketplace_element.get_items().get(0).amount > 0u32 {
+            self.marketplace_elements().insert(t_key, t_marketplace_element);
+        } else {
+            self.clear_offer(&t_key);
+        }
+
+        // Pay the creators their royalties out of the price, before the tokens leave the contract
+        let t_royalties: BigUint = self.pay_royalties(&t_bought_items, &t_payment.token_identifier, t_payment.token_nonce, &t_price);
+        let mut t_bidder_share: BigUint = t_price - &t_royalties;
+
+        // Take the fee from the proceeds if the seller pays it (never more than what is left)
+        if t_fee_config.is_paid_by_seller() {
+            if t_fee > t_bidder_share {
+                t_fee = t_bidder_share.clone();
             }
+            t_bidder_share -= &t_fee;
         }
+
+        // Send the tokens bought to the taker, and refund what was paid above the price
+        self.send().direct_multi(&t_taker_address, &t_bought_items);
+        self.send_if_not_zero(&t_taker_address, &t_payment.token_identifier, t_payment.token_nonce, &t_refund);
+        // Send the rest of the price to the bidder
+        self.send_if_not_zero(&t_bidder_address, &t_payment.token_identifier, t_payment.token_nonce, &t_bidder_share);
+        // Send the referrer its share of the fee, and keep the rest in the contract until it is claimed
+        let mut t_referral: BigUint = BigUint::zero();
+        if let Some(t_referrer_address) = t_referrer_address {
+            t_referral = self.compute_referral(&t_referrer_address, &t_fee, &t_payment.token_identifier, t_payment.token_nonce);
+            self.send_if_not_zero(&t_referrer_address, &t_payment.token_identifier, t_payment.token_nonce, &t_referral);
+            self.add_referrer_earnings(&t_referrer_address, &t_payment.token_identifier, t_payment.token_nonce, &t_referral);
+        }
+        self.accrue_fee(&t_payment.token_identifier, t_payment.token_nonce, &(&t_fee - &t_referral));
+
+        SettlementElement::new(t_paid, t_fee, t_referral, t_royalties, t_bidder_share)
     }
     /*-------------------------------------------------------------------------*
-    * Removes an element in the hashmap provided the bidder address, the taker *
-    * address, and the id. This constitutes a key.                             *
+    * Change the price of an offer, keeping the tokens in escrow. Should be    *
+    * called by the bidder.                                                    *
     * Input:                                                                   *
     * u64 representing the ID.                                                 *
-    * ManagedAddress representing the address of bidder.                       *
-    * ManagedAddress representing the address of the taker.                    *
+    * BigUint representing the new price (per unit for unit price offers).     *
+    *-------------------------------------------------------------------------*/
+    #[endpoint(updateOfferPrice)]
+    fn update_offer_price(&self, p_offer_id: u64, p_new_price: BigUint)
+    {
+        let t_key: KeyElement<Self::Api> = self.get_bidder_key(p_offer_id, "Update offer not found.");
+        let mut t_marketplace_element: MarketplaceElement<Self::Api> = self.marketplace_elements().get(&t_key).unwrap();
+        require!(!t_marketplace_element.is_barter(), "Barter offers have no price.");
+        require!(!t_marketplace_element.is_dutch_auction(), "Dutch auction prices cannot be changed.");
+
+        // Rewrite the price in storage
+        t_marketplace_element.set_price(p_new_price);
+        self.marketplace_elements().insert(t_key, t_marketplace_element);
+    }
+    /*-------------------------------------------------------------------------*
+    * Add more units to an offer listing a single fungible or semi-fungible    *
+    * token. Payable with the same token and nonce as the one listed. Should   *
+    * be called by the bidder.                                                 *
+    * Input:                                                                   *
+    * u64 representing the ID.                                                 *
+    * Optional new price (per unit for unit price offers).                     *
+    *-------------------------------------------------------------------------*/
+    #[payable("*")]
+    #[endpoint(addToOffer)]
+    fn add_to_offer(&self, p_offer_id: u64, p_new_price: OptionalValue<BigUint>)
+    {
+        let t_key: KeyElement<Self::Api> = self.get_bidder_key(p_offer_id, "Add to offer not found.");
+        let mut t_marketplace_element: MarketplaceElement<Self::Api> = self.marketplace_elements().get(&t_key).unwrap();
+        require!(t_marketplace_element.get_items().len() == 1, "Only offers listing a single token can be modified.");
+
+        // The tokens added must be the ones listed
+        let t_payment: EsdtTokenPayment = self.call_value().single_esdt();
+        let t_listed_item: EsdtTokenPayment = t_marketplace_element.get_items().get(0);
+        require!(t_payment.token_identifier == t_listed_item.token_identifier && t_payment.token_nonce == t_listed_item.token_nonce, "Incorrect token provided.");
+
+        // Update the escrowed amount, and the price if needed
+        t_marketplace_element.add_amount(&t_payment.amount);
+        if let OptionalValue::Some(t_new_price) = p_new_price {
+            require!(!t_marketplace_element.is_barter(), "Barter offers have no price.");
+            require!(!t_marketplace_element.is_dutch_auction(), "Dutch auction prices cannot be changed.");
+            t_marketplace_element.set_price(t_new_price);
+        }
+        self.marketplace_elements().insert(t_key, t_marketplace_element);
+    }
+    /*-------------------------------------------------------------------------*
+    * Take back part of the units of an offer listing a single fungible or     *
+    * semi-fungible token. Should be called by the bidder. To take back        *
+    * everything, refundOffer should be used instead.                          *
+    * Input:                                                                   *
+    * u64 representing the ID.                                                 *
+    * BigUint representing the amount to withdraw.                             *
+    * Optional new price (per unit for unit price offers).                     *
+    *-------------------------------------------------------------------------*/
+    #[endpoint(withdrawFromOffer)]
+    fn withdraw_from_offer(&self, p_offer_id: u64, p_amount: BigUint, p_new_price: OptionalValue<BigUint>)
+    {
+        let t_key: KeyElement<Self::Api> = self.get_bidder_key(p_offer_id, "Withdraw from offer not found.");
+        let mut t_marketplace_element: MarketplaceElement<Self::Api> = self.marketplace_elements().get(&t_key).unwrap();
+        require!(t_marketplace_element.get_items().len() == 1, "Only offers listing a single token can be modified.");
+
+        // Something must be left in the offer
+        let t_listed_item: EsdtTokenPayment = t_marketplace_element.get_items().get(0);
+        require!(p_amount > 0u32 && p_amount < t_listed_item.amount, "Invalid amount provided.");
+
+        // Update the escrowed amount, and the price if needed
+        t_marketplace_element.sub_amount(&p_amount);
+        if let OptionalValue::Some(t_new_price) = p_new_price {
+            require!(!t_marketplace_element.is_barter(), "Barter offers have no price.");
+            require!(!t_marketplace_element.is_dutch_auction(), "Dutch auction prices cannot be changed.");
+            t_marketplace_element.set_price(t_new_price);
+        }
+        self.marketplace_elements().insert(t_key.clone(), t_marketplace_element);
+
+        // Send the withdrawn tokens back to the bidder
+        self.send().direct_esdt(t_key.get_bidder_address(), &t_listed_item.token_identifier, t_listed_item.token_nonce, &p_amount);
+    }
+    /*-------------------------------------------------------------------------*
+    * Move an offer to a different taker, keeping the tokens in escrow and the *
+    * price. Should be called by the bidder. Any pending negotiation with the  *
+    * previous taker is dropped.                                               *
+    * Input:                                                                   *
+    * u64 representing the ID.                                                 *
+    * Address of the new taker (zero address to open the offer to anyone).     *
+    *-------------------------------------------------------------------------*/
+    #[endpoint(changeOfferTaker)]
+    fn change_offer_taker(&self, p_offer_id: u64, p_new_taker_address: ManagedAddress)
+    {
+        let t_key: KeyElement<Self::Api> = self.get_bidder_key(p_offer_id, "Change taker offer not found.");
+
+        // Move the element to its new key, keeping its ID
+        let t_marketplace_element: MarketplaceElement<Self::Api> = self.remove_element(&t_key);
+        let t_new_key = KeyElement::new(p_offer_id, t_key.get_bidder_address().clone(), p_new_taker_address);
+        self.insert_element_by_key(t_new_key, t_marketplace_element);
+    }
+    /*-------------------------------------------------------------------------*
+    * Propose a different price for an offer. Should be called by the taker of *
+    * the offer. Only one counter-offer can be pending at a time.              *
+    * Input:                                                                   *
+    * u64 representing the ID.                                                 *
+    * BigUint representing the price proposed by the taker.                    *
+    *-------------------------------------------------------------------------*/
+    #[endpoint(proposeCounter)]
+    fn propose_counter(&self, p_offer_id: u64, p_counter_price: BigUint)
+    {
+        // Get caller address
+        let t_taker_address: ManagedAddress = self.blockchain().get_caller();
+
+        // Only offers with a price and made to the caller can be negotiated
+        let t_key: KeyElement<Self::Api> = match self.get_offer_key(p_offer_id) {
+            Some(t_key) if *t_key.get_taker_address() == t_taker_address => t_key,
+            _ => sc_panic!("Counter offer not found."),
+        };
+        let t_marketplace_element: MarketplaceElement<Self::Api> = self.marketplace_elements().get(&t_key).unwrap();
+        require!(!t_marketplace_element.is_barter(), "Barter offers cannot be negotiated.");
+        require!(!t_marketplace_element.is_dutch_auction(), "Dutch auction offers cannot be negotiated.");
+        require!(!t_marketplace_element.is_expired(self.blockchain().get_block_timestamp()), "Offer has expired.");
+        require!(self.get_pending_counter(&t_key).is_none(), "A counter offer is already pending.");
+
+        // Record the proposal
+        self.negotiation_history(&t_key).push(&NegotiationEntry::new(
+            t_taker_address,
+            p_counter_price,
+            self.blockchain().get_block_timestamp(),
+            NegotiationStatus::Proposed,
+        ));
+    }
+    /*-------------------------------------------------------------------------*
+    * Accept the pending counter-offer of the taker, which becomes the new     *
+    * price of the offer. Should be called by the bidder.                      *
+    * Input:                                                                   *
+    * u64 representing the ID.                                                 *
+    *-------------------------------------------------------------------------*/
+    #[endpoint(acceptCounter)]
+    fn accept_counter(&self, p_offer_id: u64)
+    {
+        let t_key: KeyElement<Self::Api> = self.get_bidder_key(p_offer_id, "No pending counter offer.");
+        let (t_index, mut t_entry) = match self.get_pending_counter(&t_key) {
+            Some(t_pending_counter) => t_pending_counter,
+            None => sc_panic!("No pending counter offer."),
+        };
+
+        // Update the price of the offer
+        let mut t_marketplace_element: MarketplaceElement<Self::Api> = self.marketplace_elements().get(&t_key).unwrap();
+        t_marketplace_element.set_price(t_entry.get_price().clone());
+        self.marketplace_elements().insert(t_key.clone(), t_marketplace_element);
+
+        // Close the negotiation
+        t_entry.set_status(NegotiationStatus::Accepted);
+        self.negotiation_history(&t_key).set(t_index, &t_entry);
+    }
+    /*-------------------------------------------------------------------------*
+    * Reject the pending counter-offer of the taker. The price of the offer is *
+    * kept. Should be called by the bidder.                                    *
+    * Input:                                                                   *
+    * u64 representing the ID.                                                 *
+    *-------------------------------------------------------------------------*/
+    #[endpoint(rejectCounter)]
+    fn reject_counter(&self, p_offer_id: u64)
+    {
+        let t_key: KeyElement<Self::Api> = self.get_bidder_key(p_offer_id, "No pending counter offer.");
+        let (t_index, mut t_entry) = match self.get_pending_counter(&t_key) {
+            Some(t_pending_counter) => t_pending_counter,
+            None => sc_panic!("No pending counter offer."),
+        };
+
+        // Close the negotiation
+        t_entry.set_status(NegotiationStatus::Rejected);
+        self.negotiation_history(&t_key).set(t_index, &t_entry);
+    }
+    /*-------------------------------------------------------------------------*
+    * Lock a payment (EGLD or ESDT) in escrow to ask the holder of a specific  *
+    * token to sell it. Payable in the token the bid is paid in.               *
+    * Input:                                                                   *
+    * Address of the holder asked to sell (zero address for any holder).       *
+    * Identifier of the token wanted.                                          *
+    * Nonce of the token wanted.                                               *
+    * Amount of the token wanted.                                              *
     * Output:                                                                  *
-    * Monad of the MarketplaceElement.                                         *
+    * u64 representing the ID assigned to the bid.                             *
     *-------------------------------------------------------------------------*/
-    fn remove_element_by_key(&self, p_offer_id: u64, p_bidder_address: ManagedAddress, p_taker_address: ManagedAddress)-> Option<MarketplaceElement<Self::Api>>
+    #[payable("*")]
+    #[endpoint(createBid)]
+    fn create_bid(&self, p_holder_address: ManagedAddress, p_token_identifier: TokenIdentifier, p_token_nonce: u64, p_amount: BigUint) -> u64
     {
-        // Create the key
-        let t_key = KeyElement::new(            
-            p_offer_id,
-            p_bidder_address,
-            p_taker_address,
-        );
+        // Get the payment locked for the token
+        let t_payment: EgldOrEsdtTokenPayment = self.call_value().egld_or_single_esdt();
+        require!(t_payment.amount > 0u32, "No payment provided for the bid.");
+        require!(p_token_identifier.is_valid_esdt_identifier() && p_amount > 0u32, "Invalid wanted token provided.");
+
+        // Get bidder address (the bidder is the caller)
+        let t_bidder_address: ManagedAddress = self.blockchain().get_caller();
 
-        // Return monad
-        self.marketplace_elements().remove(&t_key)
+        // Verify both holder and bidder address are payable addresses not belonging to a smart contract
+        require!(!self.blockchain().is_smart_contract(&t_bidder_address) ,"Bidder address is from a smart contract.");
+        require!(p_holder_address.is_zero() || !self.blockchain().is_smart_contract(&p_holder_address) ,"Holder address is from a smart contract.");
+
+        // Assign the next ID and store the bid
+        let t_bid_id: u64 = self.last_bid_id().update(|t_last_bid_id| {
+            *t_last_bid_id += 1;
+            *t_last_bid_id
+        });
+        self.bids().insert(t_bid_id, BidElement::new(
+            t_bidder_address,
+            p_holder_address,
+            EsdtTokenPayment::new(p_token_identifier, p_token_nonce, p_amount),
+            t_payment,
+            false,
+            1u64,
+        ));
+        t_bid_id
     }
     /*-------------------------------------------------------------------------*
-    * Inserts new element into the KeyMap.                                     *
+    * Lock a payment (EGLD or ESDT) in escrow to buy several NFTs of a         *
+    * collection, whatever their nonce. Each item is paid an equal share of    *
+    * the payment. Payable in the token the bid is paid in.                    *
     * Input:                                                                   *
-    * EsdtTokenPayment structure representing the token we want to bid.        *
-    * BigUint representing the price we want to bid at.                        *
-    * u64 representing the offer id.                                           *
-    * ManagedAddress representing the address of bidder.                       *
-    * ManagedAddress representing the address to refund.                       *
+    * Identifier of the collection wanted.                                     *
+    * u64 representing the number of items wanted.                             *
+    * Optional predicate (Contains or Entry) and pattern the attributes of the *
+    * items must match. Any item of the collection is accepted otherwise.      *
+    * Output:                                                                  *
+    * u64 representing the ID assigned to the bid.                             *
     *-------------------------------------------------------------------------*/
-    fn insert_element(&self, p_esdt_structure: EsdtTokenPayment, p_price: BigUint, p_offer_id: u64, p_bidder_address: ManagedAddress, p_taker_address: ManagedAddress)
-    {        
-        // Verify both taker and bidder address are payable addresses not belonging to a smart contract
-        require!(!self.blockchain().is_smart_contract(&p_bidder_address) ,"Bidder address is from a smart contract.");
-        require!(!self.blockchain().is_smart_contract(&p_taker_address) ,"Taker address is from a smart contract.");
+    #[payable("*")]
+    #[endpoint(createCollectionBid)]
+    fn create_collection_bid(&self, p_token_identifier: TokenIdentifier, p_slots: u64, p_attribute_filter: OptionalValue<MultiValue2<AttributeMatch, ManagedBuffer>>) -> u64
+    {
+        // Get the payment locked for the items
+        let t_payment: EgldOrEsdtTokenPayment = self.call_value().egld_or_single_esdt();
+        require!(t_payment.amount > 0u32, "No payment provided for the bid.");
+        require!(p_token_identifier.is_valid_esdt_identifier(), "Invalid wanted token provided.");
+        require!(p_slots > 0, "Invalid number of items provided.");
+        require!(&t_payment.amount % p_slots == 0u32, "Payment must be divisible by the number of items.");
 
-        // Create the element
-        let t_new_element = MarketplaceElement::new(
-            p_esdt_structure.token_identifier,
-            p_esdt_structure.token_nonce,
-            p_esdt_structure.amount,
-            p_price,
-        );
+        // Get bidder address (the bidder is the caller)
+        let t_bidder_address: ManagedAddress = self.blockchain().get_caller();
+        require!(!self.blockchain().is_smart_contract(&t_bidder_address) ,"Bidder address is from a smart contract.");
 
-        // Create the key
-        let t_new_key = KeyElement::new(            
-            p_offer_id,
-            p_bidder_address,
-            p_taker_address,
+        // Create the bid, with the predicate on the attributes if any. Any holder can fill it
+        let mut t_bid = BidElement::new(
+            t_bidder_address,
+            ManagedAddress::zero(),
+            EsdtTokenPayment::new(p_token_identifier, 0u64, BigUint::from(1u32)),
+            t_payment,
+            true,
+            p_slots,
         );
+        if let OptionalValue::Some(t_attribute_filter) = p_attribute_filter {
+            let (t_attribute_match, t_attribute_pattern) = t_attribute_filter.into_tuple();
+            require!(t_attribute_match == AttributeMatch::Any || !t_attribute_pattern.is_empty(), "Invalid attribute pattern provided.");
+            t_bid.set_attribute_filter(t_attribute_match, t_attribute_pattern);
+        }
 
-        // Verify if the element is not already present
-        require!(!self.marketplace_elements().insert(t_new_key, t_new_element).is_some(), "Element already present. Try with different ID.")   
+        // Assign the next ID and store the bid
+        let t_bid_id: u64 = self.last_bid_id().update(|t_last_bid_id| {
+            *t_last_bid_id += 1;
+            *t_last_bid_id
+        });
+        self.bids().insert(t_bid_id, t_bid);
+        t_bid_id
     }
     /*-------------------------------------------------------------------------*
-    *                                                                          *
+    * Fill a bid by sending the token wanted, receiving the payment locked by  *
+    * the bidder, minus the royalties of the creator. Should be called by the  *
+    * holder named in the bid (anyone but the bidder for open bids).           *
+    * Collection bids accept any nonce of the collection whose attributes      *
+    * match the predicate of the bid, and pay one share of the payment for     *
+    * each item.                                                               *
+    * Input:                                                                   *
+    * u64 representing the ID of the bid.                                      *
     *-------------------------------------------------------------------------*/
+    #[payable("*")]
+    #[endpoint(acceptBid)]
+    fn accept_bid(&self, p_bid_id: u64)
+    {
+        // Get caller address
+        let t_holder_address: ManagedAddress = self.blockchain().get_caller();
 
-    //Storage
+        let mut t_bid: BidElement<Self::Api> = match self.bids().get(&p_bid_id) {
+            Some(t_bid) if *t_bid.get_holder_address() == t_holder_address || (t_bid.is_open() && *t_bid.get_bidder_address() != t_holder_address) => t_bid,
+            _ => sc_panic!("Accept bid not found."),
+        };
+
+        // The token sent must be the one wanted
+        let t_token: EsdtTokenPayment = self.call_value().single_esdt();
+        require!(t_bid.accepts(&t_token), "Incorrect token provided.");
+        if t_bid.has_attribute_filter() {
+            let t_token_data = self.blockchain().get_esdt_token_data(&self.blockchain().get_sc_address(), &t_token.token_identifier, t_token.token_nonce);
+            require!(t_bid.attributes_match(&t_token_data.attributes), "Token attributes do not match the bid.");
+        }
+
+        // Swap the token and one share of the payment, minus the royalties paid to the creator
+        let t_price: BigUint = t_bid.fill_slot();
+        let t_payment: &EgldOrEsdtTokenPayment = t_bid.get_payment();
+        let t_royalties: BigUint = self.pay_royalties(&ManagedVec::from_single_item(t_token.clone()), &t_payment.token_identifier, t_payment.token_nonce, &t_price);
+        self.send().direct_esdt(t_bid.get_bidder_address(), &t_token.token_identifier, t_token.token_nonce, &t_token.amount);
+        self.send_if_not_zero(&t_holder_address, &t_payment.token_identifier, t_payment.token_nonce, &(t_price - t_royalties));
+
+        // Keep the bid while items are still wanted
+        if t_bid.get_slots_left() == 0 {
+            self.bids().remove(&p_bid_id);
+        } else {
+            self.bids().insert(p_bid_id, t_bid);
+        }
+    }
     /*-------------------------------------------------------------------------*
-    *  Unordered set to store all the marketplace elements.                    *
+    * Cancel a bid, sending what is left of the locked payment back to the     *
+    * bidder. Should be called by the bidder.                                  *
+    * Input:                                                                   *
+    * u64 representing the ID of the bid.                                      *
     *-------------------------------------------------------------------------*/
-    //See storage mappers: https://docs.multiversx.com/developers/developer-reference/sc-annotations
-    #[view(getMarketplaceElements)]
-    #[storage_mapper("marketplaceElements")]
-    fn marketplace_elements(&self) -> MapMapper<KeyElement<Self::Api>, MarketplaceElement<Self::Api>>;
+    #[endpoint(cancelBid)]
+    fn cancel_bid(&self, p_bid_id: u64)
+    {
+        let t_bid: BidElement<Self::Api> = match self.bids().get(&p_bid_id) {
+            Some(t_bid) if *t_bid.get_bidder_address() == self.blockchain().get_caller() => t_bid,
+            _ => sc_panic!("Cancel bid not found."),
+        };
+        self.bids().remove(&p_bid_id);
+
+        // Refund the bidder
+        let t_payment: &EgldOrEsdtTokenPayment = t_bid.get_payment();
+        self.send().direct(t_bid.get_bidder_address(), &t_payment.token_identifier, t_payment.token_nonce, &t_payment.amount);
+    }
+    /*-------------------------------------------------------------------------*
+    * Put a basket of tokens up for an English auction, paid in EGLD. Payable  *
+    * in any token (ESDT, NFT, SFT), one or several of them.                   *
+    * Input:                                                                   *
+    * Reserve price, the lowest first bid accepted.                            *
+    * Minimum increment of each bid over the previous one.                     *
+    * End timestamp of the auction.                                            *
+    * Output:                                                                  *
+    * u64 representing the ID assigned to the auction.                         *
+    *-------------------------------------------------------------------------*/
+    #[payable("*")]
+    #[endpoint(createAuction)]
+    fn create_auction(&self, p_reserve_price: BigUint, p_min_increment: BigUint, p_end_at: u64) -> u64
+    {
+        // Get received tokens. All of them are sold together as a single bundle
+        let t_esdt_payments: ManagedVec<EsdtTokenPayment> = self.call_value().all_esdt_transfers().clone_value();
+        require!(!t_esdt_payments.is_empty(), "No tokens provided for the auction.");
+        require!(p_end_at > self.blockchain().get_block_timestamp(), "End timestamp is in the past.");
+        require!(p_reserve_price > 0u32 && p_reserve_price >= self.compute_fee(&self.get_fee_config(), &EgldOrEsdtTokenIdentifier::egld(), &p_reserve_price), "Reserve price must cover the fee.");
+        require!(p_min_increment > 0u32, "Invalid minimum increment provided.");
+
+        // Get seller address (the seller is the caller)
+        let t_seller_address: ManagedAddress = self.blockchain().get_caller();
+        require!(!self.blockchain().is_smart_contract(&t_seller_address) ,"Seller address is from a smart contract.");
+
+        // Assign the next ID and store the auction
+        let t_auction_id: u64 = self.last_auction_id().update(|t_last_auction_id| {
+            *t_last_auction_id += 1;
+            *t_last_auction_id
+        });
+        self.auctions().insert(t_auction_id, AuctionElement::new(t_seller_address, t_esdt_payments, p_reserve_price, p_min_increment, p_end_at));
+        t_auction_id
+    }
+    /*-------------------------------------------------------------------------*
+    * Bid on an auction, escrowing the EGLD sent. The bid must reach the       *
+    * reserve price, or the highest bid plus the minimum increment, and the    *
+    * previous highest bidder is refunded straight away.                       *
+    * Input:                                                                   *
+    * u64 representing the ID of the auction.                                  *
+    *-------------------------------------------------------------------------*/
+    #[payable("EGLD")]
+    #[endpoint(bidOnAuction)]
+    fn bid_on_auction(&self, p_auction_id: u64)
+    {
+        // Get caller address
+        let t_bidder_address: ManagedAddress = self.blockchain().get_caller();
+
+        let mut t_auction: AuctionElement<Self::Api> = match self.auctions().get(&p_auction_id) {
+            Some(t_auction) => t_auction,
+            None => sc_panic!("Auction not found."),
+        };
+        require!(!t_auction.has_ended(self.blockchain().get_block_timestamp()), "Auction has ended.");
+        require!(*t_auction.get_seller_address() != t_bidder_address, "Seller cannot bid on its own auction.");
+        require!(!self.blockchain().is_smart_contract(&t_bidder_address) ,"Bidder address is from a smart contract.");
+
+        // Verify the bid is high enough
+        let t_bid: BigUint = self.call_value().egld_value().clone_value();
+        require!(t_bid >= t_auction.get_min_bid(), "Bid too low.");
+
+        // Refund the previous highest bidder
+        if t_auction.has_bid() {
+            self.send().direct_egld(t_auction.get_highest_bidder(), t_auction.get_highest_bid());
+        }
+
+        t_auction.set_highest_bid(t_bidder_address, t_bid);
+        self.auctions().insert(p_auction_id, t_auction);
+    }
+    /*-------------------------------------------------------------------------*
+    * Settle an auction once it has ended: the tokens go to the highest bidder *
+    * and the highest bid, minus the royalties and the fee, to the seller.     *
+    * Without any bid, the tokens go back to the seller. Can be called by      *
+    * anyone.                                                                  *
+    * Input:                                                                   *
+    * u64 representing the ID of the auction.                                  *
+    *-------------------------------------------------------------------------*/
+    #[endpoint(settleAuction)]
+    fn settle_auction(&self, p_auction_id: u64)
+    {
+        let t_auction: AuctionElement<Self::Api> = match self.auctions().get(&p_auction_id) {
+            Some(t_auction) => t_auction,
+            None => sc_panic!("Auction not found."),
+        };
+        require!(t_auction.has_ended(self.blockchain().get_block_timestamp()), "Auction has not ended.");
+        self.auctions().remove(&p_auction_id);
+
+        if !t_auction.has_bid() {
+            // Send the whole bundle back to the seller
+            self.send().direct_multi(t_auction.get_seller_address(), t_auction.get_items());
+            return;
+        }
+
+        // Pay the creators their royalties out of the winning bid, before the tokens leave the contract
+        let t_royalties: BigUint = self.pay_royalties(t_auction.get_items(), &EgldOrEsdtTokenIdentifier::egld(), 0u64, t_auction.get_highest_bid());
+        let t_bid_left: BigUint = t_auction.get_highest_bid() - &t_royalties;
+        // Send the tokens to the winner
+        self.send().direct_multi(t_auction.get_highest_bidder(), t_auction.get_items());
+        // Send the proceeds to the seller
+        // The fee is always taken from the winning bid (never more than what is left), and may have been raised since the auction was created
+        let t_fee: BigUint = core::cmp::min(self.compute_fee(&self.get_fee_config(), &EgldOrEsdtTokenIdentifier::egld(), t_auction.get_highest_bid()), t_bid_left.clone());
+        let t_proceeds: BigUint = t_bid_left - &t_fee;
+        self.send_if_not_zero(t_auction.get_seller_address(), &EgldOrEsdtTokenIdentifier::egld(), 0u64, &t_proceeds);
+        // Keep the fee in the contract until it is claimed
+        self.accrue_fee(&EgldOrEsdtTokenIdentifier::egld(), 0u64, &t_fee);
+    }
+    /*-------------------------------------------------------------------------*
+    * Put a basket of tokens up for a sealed-bid auction, paid in EGLD.        *
+    * Bidders first commit to a hidden bid, then reveal it once the commit     *
+    * phase is over. Payable in any token (ESDT, NFT, SFT), one or several.    *
+    * Input:                                                                   *
+    * Reserve price, the lowest bid accepted.                                  *
+    * End timestamp of the commit phase.                                       *
+    * End timestamp of the reveal phase.                                       *
+    * Output:                                                                  *
+    * u64 representing the ID assigned to the auction.                         *
+    *-------------------------------------------------------------------------*/
+    #[payable("*")]
+    #[endpoint(createSealedAuction)]
+    fn create_sealed_auction(&self, p_reserve_price: BigUint, p_commit_end: u64, p_reveal_end: u64) -> u64
+    {
+        // Get received tokens. All of them are sold together as a single bundle
+        let t_esdt_payments: ManagedVec<EsdtTokenPayment> = self.call_value().all_esdt_transfers().clone_value();
+        require!(!t_esdt_payments.is_empty(), "No tokens provided for the auction.");
+        require!(p_commit_end > self.blockchain().get_block_timestamp() && p_reveal_end > p_commit_end, "Invalid auction phases.");
+        require!(p_reserve_price > 0u32 && p_reserve_price >= self.compute_fee(&self.get_fee_config(), &EgldOrEsdtTokenIdentifier::egld(), &p_reserve_price), "Reserve price must cover the fee.");
+
+        // Get seller address (the seller is the caller)
+        let t_seller_address: ManagedAddress = self.blockchain().get_caller();
+        require!(!self.blockchain().is_smart_contract(&t_seller_address) ,"Seller address is from a smart contract.");
+
+        // Assign the next ID and store the auction
+        let t_auction_id: u64 = self.last_sealed_auction_id().update(|t_last_auction_id| {
+            *t_last_auction_id += 1;
+            *t_last_auction_id
+        });
+        self.sealed_auctions().insert(t_auction_id, SealedAuctionElement::new(t_seller_address, t_esdt_payments, p_reserve_price, p_commit_end, p_reveal_end));
+        t_auction_id
+    }
+    /*-------------------------------------------------------------------------*
+    * Commit to a hidden bid during the commit phase of a sealed-bid auction,  *
+    * escrowing an EGLD deposit at least as high as the bid and the reserve    *
+    * price. Each bidder can commit only once, and a hash already committed is *
+    * rejected.                                                                *
+    * Input:                                                                   *
+    * u64 representing the ID of the auction.                                  *
+    * Hash of the bid, computed off-chain as described in computeBidHash.      *
+    *-------------------------------------------------------------------------*/
+    #[payable("EGLD")]
+    #[endpoint(commitSealedBid)]
+    fn commit_sealed_bid(&self, p_auction_id: u64, p_bid_hash: ManagedByteArray<Self::Api, 32>)
+    {
+        // Get caller address
+        let t_bidder_address: ManagedAddress = self.blockchain().get_caller();
+
+        let t_auction: SealedAuctionElement<Self::Api> = match self.sealed_auctions().get(&p_auction_id) {
+            Some(t_auction) => t_auction,
+            None => sc_panic!("Auction not found."),
+        };
+        require!(t_auction.is_commit_phase(self.blockchain().get_block_timestamp()), "Commit phase is over.");
+        require!(*t_auction.get_seller_address() != t_bidder_address, "Seller cannot bid on its own auction.");
+        require!(!self.sealed_bids(p_auction_id).contains_key(&t_bidder_address), "Bid already committed.");
+        require!(!self.sealed_bid_hashes(p_auction_id).contains_key(&p_bid_hash), "Hash already committed.");
+
+        // Escrow the deposit together with the commitment
+        let t_deposit: BigUint = self.call_value().egld_value().clone_value();
+        require!(t_deposit >= *t_auction.get_reserve_price(), "Deposit below the reserve price.");
+        self.sealed_bid_hashes(p_auction_id).insert(p_bid_hash.clone(), t_bidder_address.clone());
+        self.sealed_bids(p_auction_id).insert(t_bidder_address, SealedBid::new(p_bid_hash, t_deposit));
+    }
+    /*-------------------------------------------------------------------------*
+    * Reveal a bid during the reveal phase of a sealed-bid auction. The bid    *
+    * must match the hash committed, reach the reserve price, and be covered   *
+    * by the deposit. The highest bid revealed first wins.                     *
+    * Input:                                                                   *
+    * u64 representing the ID of the auction.                                  *
+    * BigUint representing the bid.                                            *
+    * ManagedBuffer representing the salt used to compute the hash.            *
+    *-------------------------------------------------------------------------*/
+    #[endpoint(revealSealedBid)]
+    fn reveal_sealed_bid(&self, p_auction_id: u64, p_bid: BigUint, p_salt: ManagedBuffer)
+    {
+        // Get caller address
+        let t_bidder_address: ManagedAddress = self.blockchain().get_caller();
+
+        let mut t_auction: SealedAuctionElement<Self::Api> = match self.sealed_auctions().get(&p_auction_id) {
+            Some(t_auction) => t_auction,
+            None => sc_panic!("Auction not found."),
+        };
+        require!(t_auction.is_reveal_phase(self.blockchain().get_block_timestamp()), "Not in the reveal phase.");
+
+        let mut t_sealed_bid: SealedBid<Self::Api> = match self.sealed_bids(p_auction_id).get(&t_bidder_address) {
+            Some(t_sealed_bid) => t_sealed_bid,
+            None => sc_panic!("Bid not committed."),
+        };
+        require!(!t_sealed_bid.is_revealed(), "Bid already revealed.");
+        require!(self.compute_bid_hash(t_bidder_address.clone(), p_bid.clone(), p_salt) == *t_sealed_bid.get_bid_hash(), "Bid does not match its commitment.");
+        require!(p_bid <= *t_sealed_bid.get_deposit(), "Bid exceeds the deposit.");
+        require!(p_bid >= *t_auction.get_reserve_price(), "Bid below the reserve price.");
+
+        t_sealed_bid.set_revealed();
+        self.sealed_bids(p_auction_id).insert(t_bidder_address.clone(), t_sealed_bid);
+
+        // Keep the highest bid
+        if !t_auction.has_bid() || p_bid > *t_auction.get_highest_bid() {
+            t_auction.set_highest_bid(t_bidder_address, p_bid);
+            self.sealed_auctions().insert(p_auction_id, t_auction);
+        }
+    }
+    /*-------------------------------------------------------------------------*
+    * Settle a sealed-bid auction once the reveal phase is over: the tokens go *
+    * to the highest bidder and the highest bid, minus the royalties and the   *
+    * fee, to the seller. The winner gets back what exceeds the bid in its     *
+    * deposit, and the other bidders withdraw their deposit themselves with    *
+    * withdrawSealedDeposit. Without any valid bid, the tokens go back to the  *
+    * seller. Can be called by anyone.                                         *
+    * Input:                                                                   *
+    * u64 representing the ID of the auction.                                  *
+    *-------------------------------------------------------------------------*/
+    #[endpoint(settleSealedAuction)]
+    fn settle_sealed_auction(&self, p_auction_id: u64)
+    {
+        let t_auction: SealedAuctionElement<Self::Api> = match self.sealed_auctions().get(&p_auction_id) {
+            Some(t_auction) => t_auction,
+            None => sc_panic!("Auction not found."),
+        };
+        require!(t_auction.has_ended(self.blockchain().get_block_timestamp()), "Auction has not ended.");
+        self.sealed_auctions().remove(&p_auction_id);
+
+        if !t_auction.has_bid() {
+            // Send the whole bundle back to the seller
+            self.send().direct_multi(t_auction.get_seller_address(), t_auction.get_items());
+            return;
+        }
+
+        // Refund the winner what exceeds the bid. The other bidders withdraw their deposit themselves
+        let t_winning_bid: SealedBid<Self::Api> = self.sealed_bids(p_auction_id).remove(t_auction.get_highest_bidder()).unwrap();
+        self.sealed_bid_hashes(p_auction_id).remove(t_winning_bid.get_bid_hash());
+        self.send_if_not_zero(t_auction.get_highest_bidder(), &EgldOrEsdtTokenIdentifier::egld(), 0u64, &(t_winning_bid.get_deposit() - t_auction.get_highest_bid()));
+
+        // Pay the creators their royalties out of the winning bid, before the tokens leave the contract
+        let t_royalties: BigUint = self.pay_royalties(t_auction.get_items(), &EgldOrEsdtTokenIdentifier::egld(), 0u64, t_auction.get_highest_bid());
+        let t_bid_left: BigUint = t_auction.get_highest_bid() - &t_royalties;
+        // Send the tokens to the winner
+        self.send().direct_multi(t_auction.get_highest_bidder(), t_auction.get_items());
+        // Send the proceeds to the seller
+        // The fee is always taken from the winning bid (never more than what is left), and may have been raised since the auction was created
+        let t_fee: BigUint = core::cmp::min(self.compute_fee(&self.get_fee_config(), &EgldOrEsdtTokenIdentifier::egld(), t_auction.get_highest_bid()), t_bid_left.clone());
+        let t_proceeds: BigUint = t_bid_left - &t_fee;
+        self.send_if_not_zero(t_auction.get_seller_address(), &EgldOrEsdtTokenIdentifier::egld(), 0u64, &t_proceeds);
+        // Keep the fee in the contract until it is claimed
+        self.accrue_fee(&EgldOrEsdtTokenIdentifier::egld(), 0u64, &t_fee);
+    }
+    /*-------------------------------------------------------------------------*
+    * Withdraw the deposit of a sealed bid that did not win, revealed or not,  *
+    * once the reveal phase is over. The deposit of the winner is refunded     *
+    * when the auction is settled.                                             *
+    * Input:                                                                   *
+    * u64 representing the ID of the auction.                                  *
+    *-------------------------------------------------------------------------*/
+    #[endpoint(withdrawSealedDeposit)]
+    fn withdraw_sealed_deposit(&self, p_auction_id: u64)
+    {
+        // Get caller address
+        let t_bidder_address: ManagedAddress = self.blockchain().get_caller();
+
+        // The auction is gone once settled. Until then, the winner is only known after the reveal phase
+        if let Some(t_auction) = self.sealed_auctions().get(&p_auction_id) {
+            require!(t_auction.has_ended(self.blockchain().get_block_timestamp()), "Auction has not ended.");
+            require!(*t_auction.get_highest_bidder() != t_bidder_address, "Winning bid is refunded when the auction is settled.");
+        }
+        let t_sealed_bid: SealedBid<Self::Api> = match self.sealed_bids(p_auction_id).remove(&t_bidder_address) {
+            Some(t_sealed_bid) => t_sealed_bid,
+            None => sc_panic!("Bid not committed."),
+        };
+        self.sealed_bid_hashes(p_auction_id).remove(t_sealed_bid.get_bid_hash());
+        self.send_if_not_zero(&t_bidder_address, &EgldOrEsdtTokenIdentifier::egld(), 0u64, t_sealed_bid.get_deposit());
+    }
+    /*-------------------------------------------------------------------------*
+    * Create a barter ring between three or more participants, the caller      *
+    * being one of them. Each participant commits to the token it deposits.    *
+    * Once every participant has deposited their token, the token of each      *
+    * participant goes to the next one, and that of the last participant to    *
+    * the first one.                                                           *
+    * Input:                                                                   *
+    * Legs of the ring, in order: address of the participant, and identifier,  *
+    * nonce and amount of the token it must deposit.                           *
+    * Output:                                                                  *
+    * u64 representing the ID assigned to the ring.                            *
+    *-------------------------------------------------------------------------*/
+    #[endpoint(createRing)]
+    fn create_ring(&self, p_legs: MultiValueEncoded<MultiValue4<ManagedAddress, TokenIdentifier, u64, BigUint>>) -> u64
+    {
+        // Verify the participants: distinct payable addresses not belonging to a smart contract
+        let mut t_participants: ManagedVec<ManagedAddress> = ManagedVec::new();
+        let mut t_deposits: ManagedVec<EsdtTokenPayment> = ManagedVec::new();
+        for t_leg in p_legs.into_iter() {
+            let (t_participant, t_token_identifier, t_nonce, t_amount) = t_leg.into_tuple();
+            require!(!t_participants.contains(&t_participant), "Duplicated participant.");
+            require!(!self.blockchain().is_smart_contract(&t_participant) ,"Participant address is from a smart contract.");
+            require!(t_token_identifier.is_valid_esdt_identifier() && t_amount > 0u32, "Invalid ring token provided.");
+            t_participants.push(t_participant);
+            t_deposits.push(EsdtTokenPayment::new(t_token_identifier, t_nonce, t_amount));
+        }
+        require!(t_participants.len() >= 3, "A ring needs at least three participants.");
+
+        let t_ring = RingElement::new(t_participants, t_deposits);
+        require!(t_ring.is_participant(&self.blockchain().get_caller()), "Caller is not a participant of the ring.");
+
+        // Assign the next ID and store the ring
+        let t_ring_id: u64 = self.last_ring_id().update(|t_last_ring_id| {
+            *t_last_ring_id += 1;
+            *t_last_ring_id
+        });
+        self.rings().insert(t_ring_id, t_ring);
+        t_ring_id
+    }
+    /*-------------------------------------------------------------------------*
+    * Deposit the token of the caller in a barter ring. Payable with the token *
+    * the caller committed to when the ring was created. When the last         *
+    * participant deposits, all the transfers of the ring are made.            *
+    * Input:                                                                   *
+    * u64 representing the ID of the ring.                                     *
+    *-------------------------------------------------------------------------*/
+    #[payable("*")]
+    #[endpoint(fundRing)]
+    fn fund_ring(&self, p_ring_id: u64)
+    {
+        // Get caller address
+        let t_participant: ManagedAddress = self.blockchain().get_caller();
+
+        let t_ring: RingElement<Self::Api> = match self.rings().get(&p_ring_id) {
+            Some(t_ring) if t_ring.is_participant(&t_participant) => t_ring,
+            _ => sc_panic!("Ring not found."),
+        };
+        require!(!self.ring_deposits(p_ring_id).contains_key(&t_participant), "Tokens already deposited.");
+
+        // Escrow the token of the participant, which must be the one committed to
+        let t_esdt_payments: ManagedVec<EsdtTokenPayment> = self.call_value().all_esdt_transfers().clone_value();
+        let t_expected_deposit: EsdtTokenPayment = t_ring.get_expected_deposit(&t_participant).unwrap();
+        require!(t_esdt_payments == ManagedVec::from_single_item(t_expected_deposit), "Incorrect token provided.");
+        self.ring_deposits(p_ring_id).insert(t_participant, t_esdt_payments);
+
+        // Wait for the other participants
+        if self.ring_deposits(p_ring_id).len() < t_ring.get_participants().len() {
+            return;
+        }
+
+        // Every participant has deposited: send each deposit to the next participant
+        for (t_index, t_sender) in t_ring.get_participants().iter().enumerate() {
+            let t_items: ManagedVec<EsdtTokenPayment> = self.ring_deposits(p_ring_id).get(&t_sender).unwrap();
+            self.send().direct_multi(&t_ring.get_receiver(t_index), &t_items);
+        }
+        self.ring_deposits(p_ring_id).clear();
+        self.rings().remove(&p_ring_id);
+    }
+    /*-------------------------------------------------------------------------*
+    * Cancel a barter ring before every participant has deposited, sending the *
+    * tokens deposited back to their owners. Can be called by any participant. *
+    * Input:                                                                   *
+    * u64 representing the ID of the ring.                                     *
+    *-------------------------------------------------------------------------*/
+    #[endpoint(cancelRing)]
+    fn cancel_ring(&self, p_ring_id: u64)
+    {
+        let t_is_participant: bool = match self.rings().get(&p_ring_id) {
+            Some(t_ring) => t_ring.is_participant(&self.blockchain().get_caller()),
+            None => false,
+        };
+        require!(t_is_participant, "Ring not found.");
+        self.rings().remove(&p_ring_id);
+
+        // Refund every deposit
+        for (t_participant, t_items) in self.ring_deposits(p_ring_id).iter() {
+            self.send().direct_multi(&t_participant, &t_items);
+        }
+        self.ring_deposits(p_ring_id).clear();
+    }
+    /*-------------------------------------------------------------------------*
+    * Set the fee charged when an offer is taken, in the payment token of the  *
+    * offer, and taken from the winning bid of auctions. The fee is a share of *
+    * the price, bounded by the caps of the payment token if any. Keeps who    *
+    * pays the fee. Should be called by the owner.                             *
+    * Input:                                                                   *
+    * u64 representing the fee in basis points of the price.                   *
+    *-------------------------------------------------------------------------*/
+    #[only_owner]
+    #[endpoint(setFee)]
+    fn set_fee(&self, p_basis_points: u64)
+    {
+        require!(p_basis_points <= M_BASIS_POINTS_DENOMINATOR, "Invalid fee provided.");
+        let t_paid_by_seller: bool = self.get_fee_config().is_paid_by_seller();
+        self.fee().set(FeeElement::new(p_basis_points, t_paid_by_seller));
+    }
+    /*-------------------------------------------------------------------------*
+    * Set the minimum and maximum fee charged for prices paid in a token.      *
+    * Amounts are in that token, so each payment token has its own caps.       *
+    * Tokens without caps are only charged the basis points. Should be called  *
+    * by the owner.                                                            *
+    * Input:                                                                   *
+    * EgldOrEsdtTokenIdentifier representing the payment token.                *
+    * BigUint representing the minimum fee.                                    *
+    * BigUint representing the maximum fee (0 for no maximum). Both caps set   *
+    * to 0 remove the caps of the token.                                       *
+    *-------------------------------------------------------------------------*/
+    #[only_owner]
+    #[endpoint(setFeeCaps)]
+    fn set_fee_caps(&self, p_token: EgldOrEsdtTokenIdentifier, p_min_fee: BigUint, p_max_fee: BigUint)
+    {
+        require!(p_token.is_valid(), "Invalid token provided.");
+        require!(p_max_fee == 0u32 || p_min_fee <= p_max_fee, "Minimum fee is above the maximum fee.");
+        if p_min_fee == 0u32 && p_max_fee == 0u32 {
+            self.fee_caps().remove(&p_token);
+        } else {
+            self.fee_caps().insert(p_token, (p_min_fee, p_max_fee));
+        }
+    }
+    /*-------------------------------------------------------------------------*
+    * Set who pays the fee when an offer is taken: the buyer, on top of the    *
+    * price, or the seller, out of the proceeds. Should be called by the       *
+    * owner.                                                                   *
+    * Input:                                                                   *
+    * bool representing whether the seller pays the fee.                       *
+    *-------------------------------------------------------------------------*/
+    #[only_owner]
+    #[endpoint(setFeePaidBySeller)]
+    fn set_fee_paid_by_seller(&self, p_paid_by_seller: bool)
+    {
+        let mut t_fee_config: FeeElement = self.get_fee_config();
+        t_fee_config.set_paid_by_seller(p_paid_by_seller);
+        self.fee().set(t_fee_config);
+    }
+    /*-------------------------------------------------------------------------*
+    * Set the fee charged when an offer is created. Should be called by the    *
+    * owner.                                                                   *
+    * Input:                                                                   *
+    * TokenIdentifier representing the (fungible) token of the listing fee.    *
+    * BigUint representing the listing fee. 0 removes the listing fee.         *
+    *-------------------------------------------------------------------------*/
+    #[only_owner]
+    #[endpoint(setListingFee)]
+    fn set_listing_fee(&self, p_token_identifier: TokenIdentifier, p_amount: BigUint)
+    {
+        if p_amount == 0u32 {
+            self.listing_fee().clear();
+            return;
+        }
+        require!(p_token_identifier.is_valid_esdt_identifier(), "Invalid listing fee token provided.");
+        self.listing_fee().set(EsdtTokenPayment::new(p_token_identifier, 0u64, p_amount));
+    }
+    /*-------------------------------------------------------------------------*
+    * Set the address receiving the claimed fees, unless the owner claims them *
+    * to another address. Should be called by the owner.                       *
+    * Input:                                                                   *
+    * ManagedAddress representing the fee recipient (zero address for the      *
+    * owner).                                                                  *
+    *-------------------------------------------------------------------------*/
+    #[only_owner]
+    #[endpoint(setFeeRecipient)]
+    fn set_fee_recipient(&self, p_recipient_address: ManagedAddress)
+    {
+        if p_recipient_address.is_zero() {
+            self.fee_recipient().clear();
+            return;
+        }
+        self.fee_recipient().set(p_recipient_address);
+    }
+    /*-------------------------------------------------------------------------*
+    * Approve a referrer (marketplace, wallet), or change its share of the     *
+    * fee and its caps. Should be called by the owner.                         *
+    * Input:                                                                   *
+    * ManagedAddress representing the referrer.                                *
+    * u64 representing the share of the fee sent to the referrer, in basis     *
+    * points.                                                                  *
+    * Optional caps: token, nonce and most the referrer can earn in that token *
+    * over its lifetime. Its earnings in other tokens are not capped.          *
+    *-------------------------------------------------------------------------*/
+    #[only_owner]
+    #[endpoint(approveReferrer)]
+    fn approve_referrer(&self, p_referrer_address: ManagedAddress, p_share: u64, p_caps: MultiValueEncoded<MultiValue3<EgldOrEsdtTokenIdentifier, u64, BigUint>>)
+    {
+        require!(!p_referrer_address.is_zero(), "Invalid referrer provided.");
+        require!(p_share <= M_BASIS_POINTS_DENOMINATOR, "Invalid referrer share provided.");
+        let mut t_caps: ManagedVec<ReferrerCapElement<Self::Api>> = ManagedVec::new();
+        for t_cap in p_caps.into_iter() {
+            let (t_token, t_nonce, t_amount) = t_cap.into_tuple();
+            require!(t_token.is_valid(), "Invalid token provided.");
+            t_caps.push(ReferrerCapElement::new(t_token, t_nonce, t_amount));
+        }
+        self.referrers().insert(p_referrer_address, ReferrerElement::new(p_share, t_caps));
+    }
+    /*-------------------------------------------------------------------------*
+    * Revoke a referrer. Its lifetime earnings are kept. Should be called by   *
+    * the owner.                                                               *
+    * Input:                                                                   *
+    * ManagedAddress representing the referrer.                                *
+    *-------------------------------------------------------------------------*/
+    #[only_owner]
+    #[endpoint(revokeReferrer)]
+    fn revoke_referrer(&self, p_referrer_address: ManagedAddress)
+    {
+        require!(self.referrers().remove(&p_referrer_address).is_some(), "Referrer not approved.");
+    }
+    /*-------------------------------------------------------------------------*
+    * Set the beneficiaries the claimed fees are split among, replacing the    *
+    * previous ones. Their shares must add up to 10000 basis points. Should be *
+    * called by the owner.                                                     *
+    * Input:                                                                   *
+    * List of beneficiaries (address, share in basis points). An empty list    *
+    * sends the claimed fees to the fee recipient again.                       *
+    *-------------------------------------------------------------------------*/
+    #[only_owner]
+    #[endpoint(setFeeBeneficiaries)]
+    fn set_fee_beneficiaries(&self, p_beneficiaries: MultiValueEncoded<MultiValue2<ManagedAddress, u64>>)
+    {
+        self.fee_beneficiaries().clear();
+        if p_beneficiaries.is_empty() {
+            return;
+        }
+        let mut t_total_share: u64 = 0;
+        for t_beneficiary in p_beneficiaries.into_iter() {
+            let (t_beneficiary_address, t_share) = t_beneficiary.into_tuple();
+            require!(!t_beneficiary_address.is_zero() && t_share > 0 && t_share <= M_BASIS_POINTS_DENOMINATOR, "Invalid beneficiary provided.");
+            require!(self.fee_beneficiaries().insert(t_beneficiary_address, t_share).is_none(), "Duplicate beneficiary provided.");
+            t_total_share += t_share;
+        }
+        require!(t_total_share == M_BASIS_POINTS_DENOMINATOR, "Beneficiary shares must add up to 10000 basis points.");
+    }
+    /*-------------------------------------------------------------------------*
+    * Send the fees kept by the contract and reset them, in every token or in  *
+    * the listed ones only, to claim them in batches. If beneficiaries are     *
+    * set, the fees of each token are split among them by their shares (the    *
+    * last one also gets the rounding remainder). Should be called by the      *
+    * owner.                                                                   *
+    * Input:                                                                   *
+    * Optional ManagedAddress representing the receiver of all the fees. If    *
+    * not provided (or zero address), the beneficiaries, or the fee recipient  *
+    * if there are none.                                                       *
+    * Optional list of tokens (identifier, nonce) to claim the fees of. If not *
+    * provided, the fees of every token.                                       *
+    *-------------------------------------------------------------------------*/
+    #[only_owner]
+    #[endpoint(claimFees)]
+    fn claim_fees(&self, p_recipient_address: OptionalValue<ManagedAddress>, p_tokens: MultiValueEncoded<MultiValue2<EgldOrEsdtTokenIdentifier, u64>>)
+    {
+        require!(!self.accrued_fees().is_empty(), "No fees to claim.");
+        let t_recipient_address: Option<ManagedAddress> = match p_recipient_address {
+            OptionalValue::Some(t_recipient_address) if !t_recipient_address.is_zero() => Some(t_recipient_address),
+            _ if self.fee_beneficiaries().is_empty() => Some(self.get_fee_recipient()),
+            _ => None,
+        };
+        if p_tokens.is_empty() {
+            for ((t_token, t_nonce), t_amount) in self.accrued_fees().iter() {
+                self.send_fees(&t_recipient_address, &t_token, t_nonce, &t_amount);
+            }
+            self.accrued_fees().clear();
+            return;
+        }
+        // Claim the listed tokens only, leaving the others for later
+        for t_fee_token in p_tokens.into_iter() {
+            let (t_token, t_nonce) = t_fee_token.into_tuple();
+            let t_amount: BigUint = match self.accrued_fees().remove(&(t_token.clone(), t_nonce)) {
+                Some(t_amount) => t_amount,
+                None => sc_panic!("No fees to claim in this token."),
+            };
+            self.send_fees(&t_recipient_address, &t_token, t_nonce, &t_amount);
+        }
+    }
+    /*-------------------------------------------------------------------------*
+    * Set the token whose holders get a discount on the fee when they take an  *
+    * offer. Should be called by the owner.                                    *
+    * Input:                                                                   *
+    * TokenIdentifier representing the (fungible) discount token.              *
+    *-------------------------------------------------------------------------*/
+    #[only_owner]
+    #[endpoint(setDiscountToken)]
+    fn set_discount_token(&self, p_token_identifier: TokenIdentifier)
+    {
+        require!(p_token_identifier.is_valid_esdt_identifier(), "Invalid discount token provided.");
+        self.discount_token().set(p_token_identifier);
+    }
+    /*-------------------------------------------------------------------------*
+    * Set a discount tier: takers holding at least a balance of the discount   *
+    * token get a discount on the fee. The best tier reached applies. Should   *
+    * be called by the owner.                                                  *
+    * Input:                                                                   *
+    * BigUint representing the minimum balance of the tier.                    *
+    * u64 representing the discount in basis points of the fee. 0 removes the  *
+    * tier.                                                                    *
+    *-------------------------------------------------------------------------*/
+    #[only_owner]
+    #[endpoint(setDiscountTier)]
+    fn set_discount_tier(&self, p_min_balance: BigUint, p_discount: u64)
+    {
+        require!(p_discount <= M_BASIS_POINTS_DENOMINATOR, "Invalid discount provided.");
+        if p_discount == 0 {
+            self.discount_tiers().remove(&p_min_balance);
+            return;
+        }
+        self.discount_tiers().insert(p_min_balance, p_discount);
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the last entry of the negotiation history of an offer, if it is  *
+    * a counter-offer still waiting for an answer from the bidder.             *
+    * Input:                                                                   *
+    * KeyElement representing the offer.                                       *
+    * Output:                                                                  *
+    * Monad of the index and the NegotiationEntry.                             *
+    *-------------------------------------------------------------------------*/
+    fn get_pending_counter(&self, p_key: &KeyElement<Self::Api>)-> Option<(usize, NegotiationEntry<Self::Api>)>
+    {
+        let t_history = self.negotiation_history(p_key);
+        if t_history.is_empty() {
+            return None;
+        }
+
+        let t_index: usize = t_history.len();
+        let t_entry: NegotiationEntry<Self::Api> = t_history.get(t_index);
+        if t_entry.get_status() == NegotiationStatus::Proposed {
+            Some((t_index, t_entry))
+        } else {
+            None
+        }
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the key under which an offer is stored, given its ID.            *
+    * Input:                                                                   *
+    * u64 representing the ID.                                                 *
+    * Output:                                                                  *
+    * Monad of the KeyElement.                                                 *
+    *-------------------------------------------------------------------------*/
+    fn get_offer_key(&self, p_offer_id: u64)-> Option<KeyElement<Self::Api>>
+    {
+        if self.offer_key(p_offer_id).is_empty() {
+            return None;
+        }
+        Some(self.offer_key(p_offer_id).get())
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the key of an offer created by the caller, failing with the      *
+    * message provided if the offer does not exist or belongs to someone else. *
+    * Input:                                                                   *
+    * u64 representing the ID.                                                 *
+    * Error message if the offer is not found.                                 *
+    * Output:                                                                  *
+    * KeyElement of the offer.                                                 *
+    *-------------------------------------------------------------------------*/
+    fn get_bidder_key(&self, p_offer_id: u64, p_error: &str)-> KeyElement<Self::Api>
+    {
+        match self.get_offer_key(p_offer_id) {
+            Some(t_key) if *t_key.get_bidder_address() == self.blockchain().get_caller() => t_key,
+            _ => sc_panic!(p_error),
+        }
+    }
+    /*-------------------------------------------------------------------------*
+    * Finds the key of the offer a taker wants to take: the offer must be made *
+    * to the taker, or open to anyone. The bidder cannot take its own open     *
+    * offer.                                                                   *
+    * Input:                                                                   *
+    * u64 representing the ID.                                                 *
+    * ManagedAddress representing the address of the taker.                    *
+    * Output:                                                                  *
+    * Monad of the KeyElement.                                                 *
+    *-------------------------------------------------------------------------*/
+    fn find_take_key(&self, p_offer_id: u64, p_taker_address: &ManagedAddress)-> Option<KeyElement<Self::Api>>
+    {
+        let t_key: KeyElement<Self::Api> = self.get_offer_key(p_offer_id)?;
+        if t_key.get_taker_address() == p_taker_address || (t_key.is_open() && t_key.get_bidder_address() != p_taker_address) {
+            return Some(t_key);
+        }
+
+        None
+    }
+    /*-------------------------------------------------------------------------*
+    * Removes an element from the hashmap, together with its ID and its        *
+    * negotiation history.                                                     *
+    * Input:                                                                   *
+    * KeyElement representing the offer.                                       *
+    * Output:                                                                  *
+    * The MarketplaceElement removed.                                          *
+    *-------------------------------------------------------------------------*/
+    fn remove_element(&self, p_key: &KeyElement<Self::Api>)-> MarketplaceElement<Self::Api>
+    {
+        self.clear_offer(p_key);
+        self.marketplace_elements().remove(p_key).unwrap()
+    }
+    /*-------------------------------------------------------------------------*
+    * Forgets the ID and the negotiation history of an offer that is no longer *
+    * listed (or is being moved to another key).                               *
+    * Input:                                                                   *
+    * KeyElement representing the offer.                                       *
+    *-------------------------------------------------------------------------*/
+    fn clear_offer(&self, p_key: &KeyElement<Self::Api>)
+    {
+        self.offer_key(p_key.get_offer_id()).clear();
+        self.negotiation_history(p_key).clear();
+    }
+    /*-------------------------------------------------------------------------*
+    * Gets the tokens sent to create an offer. If a listing fee is set, it     *
+    * must be the last token sent: it is taken out of them and kept with the   *
+    * other fees.                                                              *
+    * Output:                                                                  *
+    * ManagedVec of EsdtTokenPayment representing the tokens to list.          *
+    *-------------------------------------------------------------------------*/
+    fn take_listing_fee(&self)-> ManagedVec<EsdtTokenPayment>
+    {
+        let mut t_esdt_payments: ManagedVec<EsdtTokenPayment> = self.call_value().all_esdt_transfers().clone_value();
+        if self.listing_fee().is_empty() {
+            return t_esdt_payments;
+        }
+        let t_listing_fee: EsdtTokenPayment = self.listing_fee().get();
+        require!(!t_esdt_payments.is_empty(), "Incorrect listing fee provided.");
+        let t_last_index: usize = t_esdt_payments.len() - 1;
+        let t_fee_payment: EsdtTokenPayment = t_esdt_payments.get(t_last_index);
+        require!(t_fee_payment.token_identifier == t_listing_fee.token_identifier &&
+                 t_fee_payment.token_nonce == 0 &&
+                 t_fee_payment.amount == t_listing_fee.amount, "Incorrect listing fee provided.");
+        t_esdt_payments.remove(t_last_index);
+        self.accrue_fee(&EgldOrEsdtTokenIdentifier::esdt(t_fee_payment.token_identifier), 0u64, &t_fee_payment.amount);
+        t_esdt_payments
+    }
+    /*-------------------------------------------------------------------------*
+    * Inserts new element into the KeyMap, under a new ID.                     *
+    * Input:                                                                   *
+    * ManagedVec of EsdtTokenPayment representing the tokens we want to bid.   *
+    * ManagedVec of EsdtTokenPayment representing the tokens wanted in return  *
+    * (empty if the offer has a price).                                        *
+    * EgldOrEsdtTokenIdentifier representing the token the price is paid in.   *
+    * u64 representing the nonce of the payment token.                         *
+    * BigUint representing the price we want to bid at.                        *
+    * bool indicating if the price is per unit of the (single) token listed.   *
+    * u64 representing the expiry timestamp (0 if the offer never expires).    *
+    * ManagedAddress representing the address of bidder.                       *
+    * ManagedAddress representing the address to refund.                       *
+    * Output:                                                                  *
+    * u64 representing the ID assigned to the offer.                           *
+    *-------------------------------------------------------------------------*/
+    #[allow(clippy::too_many_arguments)]
+    fn insert_element(&self, p_esdt_payments: ManagedVec<EsdtTokenPayment>, p_wanted_tokens: ManagedVec<EsdtTokenPayment>, p_payment_token: EgldOrEsdtTokenIdentifier, p_payment_nonce: u64, p_price: BigUint, p_unit_price: bool, p_expires_at: u64, p_bidder_address: ManagedAddress, p_taker_address: ManagedAddress)-> u64
+    {        
+        // Create the element
+        let t_new_element = MarketplaceElement::new(
+            p_esdt_payments,
+            p_wanted_tokens,
+            p_payment_token,
+            p_payment_nonce,
+            p_price,
+            p_unit_price,
+            p_expires_at,
+        );
+
+        self.insert_new_element(t_new_element, p_bidder_address, p_taker_address)
+    }
+    /*-------------------------------------------------------------------------*
+    * Inserts an element built by the caller into the KeyMap, under a new ID.  *
+    * Input:                                                                   *
+    * MarketplaceElement to insert.                                            *
+    * ManagedAddress representing the address of bidder.                       *
+    * ManagedAddress representing the address to refund.                       *
+    * Output:                                                                  *
+    * u64 representing the ID assigned to the offer.                           *
+    *-------------------------------------------------------------------------*/
+    fn insert_new_element(&self, p_element: MarketplaceElement<Self::Api>, p_bidder_address: ManagedAddress, p_taker_address: ManagedAddress)-> u64
+    {
+        // Verify the offer does not expire in the past
+        let t_expires_at: u64 = p_element.get_expires_at();
+        require!(t_expires_at == 0 || t_expires_at > self.blockchain().get_block_timestamp(), "Expiry timestamp is in the past.");
+
+        // Assign the next ID
+        let t_offer_id: u64 = self.last_offer_id().update(|t_last_offer_id| {
+            *t_last_offer_id += 1;
+            *t_last_offer_id
+        });
+
+        // Insert it under its key
+        self.insert_element_by_key(KeyElement::new(t_offer_id, p_bidder_address, p_taker_address), p_element);
+        t_offer_id
+    }
+    /*-------------------------------------------------------------------------*
+    * Inserts an existing element into the KeyMap, under the key made of the   *
+    * bidder address, the taker address, and the id, and indexes it by its id. *
+    * Input:                                                                   *
+    * KeyElement representing the offer.                                       *
+    * MarketplaceElement to insert.                                            *
+    *-------------------------------------------------------------------------*/
+    fn insert_element_by_key(&self, p_key: KeyElement<Self::Api>, p_element: MarketplaceElement<Self::Api>)
+    {
+        // Verify both taker and bidder address are payable addresses not belonging to a smart contract.
+        // The zero address as taker means the offer is open to anyone
+        require!(!self.blockchain().is_smart_contract(p_key.get_bidder_address()) ,"Bidder address is from a smart contract.");
+        require!(p_key.is_open() || !self.blockchain().is_smart_contract(p_key.get_taker_address()) ,"Taker address is from a smart contract.");
+
+        // Verify if the element is not already present
+        require!(self.offer_key(p_key.get_offer_id()).is_empty(), "Element already present. Try with different ID.");
+        self.offer_key(p_key.get_offer_id()).set(&p_key);
+        self.marketplace_elements().insert(p_key, p_element);
+    }
+    /*-------------------------------------------------------------------------*
+    * Computes the royalties owed to the creators of the tokens sold for a     *
+    * price. The price is split equally between the NFTs and SFTs only, and    *
+    * each of them owes its royalties on its share. Fungible tokens owe none   *
+    * and take no share, so bundling them does not lower the royalties.        *
+    * The tokens must be held by the contract.                                 *
+    * Input:                                                                   *
+    * ManagedVec representing the tokens sold.                                 *
+    * BigUint representing the price of the tokens.                            *
+    * Output:                                                                  *
+    * ManagedVec of RoyaltyElement representing the royalties of each token.   *
+    *-------------------------------------------------------------------------*/
+    fn get_royalties(&self, p_items: &ManagedVec<EsdtTokenPayment>, p_price: &BigUint)-> ManagedVec<RoyaltyElement<Self::Api>>
+    {
+        let mut t_royalties: ManagedVec<RoyaltyElement<Self::Api>> = ManagedVec::new();
+        let t_nft_count: u64 = p_items.iter().filter(|t_item| t_item.token_nonce != 0).count() as u64;
+        if t_nft_count == 0 {
+            return t_royalties;
+        }
+        let t_sc_address: ManagedAddress = self.blockchain().get_sc_address();
+        let t_item_share: BigUint = p_price / t_nft_count;
+        for t_item in p_items.iter() {
+            if t_item.token_nonce == 0 {
+                continue;
+            }
+            let t_token_data = self.blockchain().get_esdt_token_data(&t_sc_address, &t_item.token_identifier, t_item.token_nonce);
+            let t_amount: BigUint = &t_item_share * &t_token_data.royalties / M_BASIS_POINTS_DENOMINATOR;
+            if t_amount > 0u32 {
+                t_royalties.push(RoyaltyElement::new(t_token_data.creator, t_amount));
+            }
+        }
+        t_royalties
+    }
+    /*-------------------------------------------------------------------------*
+    * Pays the creators of the tokens sold their royalties out of the price.   *
+    * The tokens must still be held by the contract.                           *
+    * Input:                                                                   *
+    * ManagedVec representing the tokens sold.                                 *
+    * EgldOrEsdtTokenIdentifier representing the token of the price.           *
+    * u64 representing the nonce of the token.                                 *
+    * BigUint representing the price of the tokens.                            *
+    * Output:                                                                  *
+    * BigUint representing the royalties paid.                                 *
+    *-------------------------------------------------------------------------*/
+    fn pay_royalties(&self, p_items: &ManagedVec<EsdtTokenPayment>, p_token: &EgldOrEsdtTokenIdentifier, p_nonce: u64, p_price: &BigUint)-> BigUint
+    {
+        let mut t_royalties_paid: BigUint = BigUint::zero();
+        for t_royalty in self.get_royalties(p_items, p_price).iter() {
+            t_royalties_paid += t_royalty.get_amount();
+            self.send_if_not_zero(t_royalty.get_creator_address(), p_token, p_nonce, t_royalty.get_amount());
+        }
+        t_royalties_paid
+    }
+    /*-------------------------------------------------------------------------*
+    * Pays the royalties of a barter swap. The fungible tokens of one side are *
+    * the price of the NFTs and SFTs of the other side, and each of them pays  *
+    * the royalties of those NFTs and SFTs.                                    *
+    * Input:                                                                   *
+    * ManagedVec representing the tokens given by one side.                    *
+    * ManagedVec representing the tokens received in exchange.                 *
+    * Output:                                                                  *
+    * ManagedVec representing the tokens given, minus the royalties paid.      *
+    *-------------------------------------------------------------------------*/
+    fn pay_barter_royalties(&self, p_tokens: &ManagedVec<EsdtTokenPayment>, p_items: &ManagedVec<EsdtTokenPayment>)-> ManagedVec<EsdtTokenPayment>
+    {
+        let mut t_tokens_left: ManagedVec<EsdtTokenPayment> = ManagedVec::new();
+        for t_token in p_tokens.iter() {
+            let mut t_amount: BigUint = t_token.amount.clone();
+            if t_token.token_nonce == 0 {
+                t_amount -= self.pay_royalties(p_items, &EgldOrEsdtTokenIdentifier::esdt(t_token.token_identifier.clone()), 0u64, &t_token.amount);
+            }
+            if t_amount > 0u32 {
+                t_tokens_left.push(EsdtTokenPayment::new(t_token.token_identifier, t_token.token_nonce, t_amount));
+            }
+        }
+        t_tokens_left
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the fee configuration. No fee, paid by the buyer, if the owner   *
+    * never set it.                                                            *
+    * Output:                                                                  *
+    * FeeElement representing the fee configuration.                           *
+    *-------------------------------------------------------------------------*/
+    fn get_fee_config(&self)-> FeeElement
+    {
+        if self.fee().is_empty() {
+            return FeeElement::new(0u64, false);
+        }
+        self.fee().get()
+    }
+    /*-------------------------------------------------------------------------*
+    * Computes the fee for a price, bounded by the caps of the payment token   *
+    * if the owner set any.                                                    *
+    * Input:                                                                   *
+    * FeeElement representing the fee configuration.                           *
+    * EgldOrEsdtTokenIdentifier representing the payment token.                *
+    * BigUint representing the price.                                          *
+    * Output:                                                                  *
+    * BigUint representing the fee.                                            *
+    *-------------------------------------------------------------------------*/
+    fn compute_fee(&self, p_fee_config: &FeeElement, p_token: &EgldOrEsdtTokenIdentifier, p_price: &BigUint)-> BigUint
+    {
+        let (t_min_fee, t_max_fee): (BigUint, BigUint) = self.fee_caps().get(p_token).unwrap_or_default();
+        p_fee_config.compute_fee(p_price, &t_min_fee, &t_max_fee, M_BASIS_POINTS_DENOMINATOR)
+    }
+    /*-------------------------------------------------------------------------*
+    * Adds a fee to the fees kept by the contract, in the token it was paid    *
+    * in.                                                                      *
+    * Input:                                                                   *
+    * EgldOrEsdtTokenIdentifier representing the token of the fee.             *
+    * u64 representing the nonce of the token.                                 *
+    * BigUint representing the fee.                                            *
+    *-------------------------------------------------------------------------*/
+    fn accrue_fee(&self, p_token: &EgldOrEsdtTokenIdentifier, p_nonce: u64, p_amount: &BigUint)
+    {
+        if *p_amount == 0u32 {
+            return;
+        }
+        let t_key = (p_token.clone(), p_nonce);
+        let t_accrued: BigUint = self.accrued_fees().get(&t_key).unwrap_or_default();
+        self.accrued_fees().insert(t_key, t_accrued + p_amount);
+    }
+    /*-------------------------------------------------------------------------*
+    * Sends the claimed fees of a token to the receiver, or splits them among  *
+    * the beneficiaries by their shares if there is no receiver.               *
+    * Input:                                                                   *
+    * Option of ManagedAddress representing the receiver of the fees.          *
+    * EgldOrEsdtTokenIdentifier representing the token of the fees.            *
+    * u64 representing the nonce of the token.                                 *
+    * BigUint representing the fees.                                           *
+    *-------------------------------------------------------------------------*/
+    fn send_fees(&self, p_recipient_address: &Option<ManagedAddress>, p_token: &EgldOrEsdtTokenIdentifier, p_nonce: u64, p_amount: &BigUint)
+    {
+        if let Some(t_recipient_address) = p_recipient_address {
+            self.send_if_not_zero(t_recipient_address, p_token, p_nonce, p_amount);
+            return;
+        }
+        // Split the fees among the beneficiaries
+        let t_beneficiary_count: usize = self.fee_beneficiaries().len();
+        let mut t_left: BigUint = p_amount.clone();
+        for (t_index, (t_beneficiary_address, t_share)) in self.fee_beneficiaries().iter().enumerate() {
+            let t_part: BigUint = if t_index + 1 == t_beneficiary_count { t_left.clone() } else { p_amount * t_share / M_BASIS_POINTS_DENOMINATOR };
+            t_left -= &t_part;
+            self.send_if_not_zero(&t_beneficiary_address, p_token, p_nonce, &t_part);
+        }
+    }
+    /*-------------------------------------------------------------------------*
+    * Computes the discount on the fee an address gets when taking an offer.   *
+    * Input:                                                                   *
+    * ManagedAddress representing the taker.                                   *
+    * Output:                                                                  *
+    * u64 representing the discount in basis points, 0 if there is no          *
+    * discount token or the taker is in another shard.                         *
+    *-------------------------------------------------------------------------*/
+    fn compute_fee_discount(&self, p_address: &ManagedAddress)-> u64
+    {
+        if self.discount_token().is_empty() || !self.is_same_shard(p_address) {
+            return 0;
+        }
+        let t_balance: BigUint = self.blockchain().get_esdt_balance(p_address, &self.discount_token().get(), 0u64);
+        best_discount(&t_balance, self.discount_tiers().iter())
+    }
+    /*-------------------------------------------------------------------------*
+    * Checks whether an address is in the shard of the contract.               *
+    * Input:                                                                   *
+    * ManagedAddress representing the address.                                 *
+    * Output:                                                                  *
+    * bool representing whether the address is in the shard of the contract.   *
+    *-------------------------------------------------------------------------*/
+    fn is_same_shard(&self, p_address: &ManagedAddress)-> bool
+    {
+        self.blockchain().get_shard_of_address(p_address) == self.blockchain().get_shard_of_address(&self.blockchain().get_sc_address())
+    }
+    /*-------------------------------------------------------------------------*
+    * Computes the share of a fee sent to a referrer, never taking its         *
+    * lifetime earnings in the payment token above its cap.                    *
+    * Input:                                                                   *
+    * ManagedAddress representing the approved referrer.                       *
+    * BigUint representing the fee.                                            *
+    * EgldOrEsdtTokenIdentifier representing the token of the fee.             *
+    * u64 representing the nonce of the token.                                 *
+    * Output:                                                                  *
+    * BigUint representing the referral.                                       *
+    *-------------------------------------------------------------------------*/
+    fn compute_referral(&self, p_referrer_address: &ManagedAddress, p_fee: &BigUint, p_token: &EgldOrEsdtTokenIdentifier, p_nonce: u64)-> BigUint
+    {
+        let t_referrer: ReferrerElement<Self::Api> = self.referrers().get(p_referrer_address).unwrap();
+        let t_referral: BigUint = p_fee * t_referrer.get_share() / M_BASIS_POINTS_DENOMINATOR;
+        let t_cap: BigUint = match t_referrer.get_cap(p_token, p_nonce) {
+            Some(t_cap) => t_cap,
+            None => return t_referral,
+        };
+        let t_earned: BigUint = self.referrer_earnings(p_referrer_address).get(&(p_token.clone(), p_nonce)).unwrap_or_default();
+        if t_earned >= t_cap {
+            return BigUint::zero();
+        }
+        core::cmp::min(t_referral, t_cap - t_earned)
+    }
+    /*-------------------------------------------------------------------------*
+    * Adds a referral to the lifetime earnings of a referrer, in the token it  *
+    * was paid in.                                                             *
+    * Input:                                                                   *
+    * ManagedAddress representing the referrer.                                *
+    * EgldOrEsdtTokenIdentifier representing the token of the referral.        *
+    * u64 representing the nonce of the token.                                 *
+    * BigUint representing the referral.                                       *
+    *-------------------------------------------------------------------------*/
+    fn add_referrer_earnings(&self, p_referrer_address: &ManagedAddress, p_token: &EgldOrEsdtTokenIdentifier, p_nonce: u64, p_amount: &BigUint)
+    {
+        if *p_amount == 0u32 {
+            return;
+        }
+        let t_key = (p_token.clone(), p_nonce);
+        let t_earned: BigUint = self.referrer_earnings(p_referrer_address).get(&t_key).unwrap_or_default();
+        self.referrer_earnings(p_referrer_address).insert(t_key, t_earned + p_amount);
+    }
+    /*-------------------------------------------------------------------------*
+    * Sends EGLD or an ESDT to an address, skipping empty transfers (ESDT      *
+    * transfers of zero tokens are rejected by the protocol).                  *
+    * Input:                                                                   *
+    * ManagedAddress representing the receiver.                                *
+    * EgldOrEsdtTokenIdentifier representing the token to send.                *
+    * u64 representing the nonce of the token.                                 *
+    * BigUint representing the amount to send.                                 *
+    *-------------------------------------------------------------------------*/
+    fn send_if_not_zero(&self, p_to: &ManagedAddress, p_token: &EgldOrEsdtTokenIdentifier, p_nonce: u64, p_amount: &BigUint)
+    {
+        if *p_amount > 0u32 {
+            self.send().direct(p_to, p_token, p_nonce, p_amount);
+        }
+    }
+    /*-------------------------------------------------------------------------*
+    *                                                                          *
+    *-------------------------------------------------------------------------*/
+
+    //Views
+    /*-------------------------------------------------------------------------*
+    * Returns all the marketplace elements together with their status, so that *
+    * expired offers are reported as such.                                     *
+    *-------------------------------------------------------------------------*/
+    #[view(getMarketplaceElements)]
+    fn get_marketplace_elements(&self) -> MultiValueEncoded<MultiValue3<KeyElement<Self::Api>, MarketplaceElement<Self::Api>, OfferStatus>>
+    {
+        let t_timestamp: u64 = self.blockchain().get_block_timestamp();
+        let mut t_result = MultiValueEncoded::new();
+        for (t_key, t_marketplace_element) in self.marketplace_elements().iter() {
+            let t_status = t_marketplace_element.get_status(t_timestamp);
+            t_result.push((t_key, t_marketplace_element, t_status).into());
+        }
+        t_result
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the fee charged when an offer is taken, and who pays it.         *
+    *-------------------------------------------------------------------------*/
+    #[view(getFee)]
+    fn get_fee(&self) -> FeeElement
+    {
+        self.get_fee_config()
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the minimum and maximum fee of each payment token with caps.     *
+    *-------------------------------------------------------------------------*/
+    #[view(getFeeCaps)]
+    fn get_fee_caps(&self) -> MultiValueEncoded<MultiValue3<EgldOrEsdtTokenIdentifier, BigUint, BigUint>>
+    {
+        let mut t_result = MultiValueEncoded::new();
+        for (t_token, (t_min_fee, t_max_fee)) in self.fee_caps().iter() {
+            t_result.push((t_token, t_min_fee, t_max_fee).into());
+        }
+        t_result
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the listing fee charged when an offer is created, if any.        *
+    *-------------------------------------------------------------------------*/
+    #[view(getListingFee)]
+    fn get_listing_fee(&self) -> OptionalValue<EsdtTokenPayment>
+    {
+        if self.listing_fee().is_empty() {
+            return OptionalValue::None;
+        }
+        OptionalValue::Some(self.listing_fee().get())
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the fees kept by the contract and not claimed yet, by token.     *
+    *-------------------------------------------------------------------------*/
+    #[view(getAccruedFees)]
+    fn get_accrued_fees(&self) -> MultiValueEncoded<MultiValue3<EgldOrEsdtTokenIdentifier, u64, BigUint>>
+    {
+        let mut t_result = MultiValueEncoded::new();
+        for ((t_token, t_nonce), t_amount) in self.accrued_fees().iter() {
+            t_result.push((t_token, t_nonce, t_amount).into());
+        }
+        t_result
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the approved referrers, with their share of the fee in basis     *
+    * points and the caps of their lifetime earnings.                          *
+    *-------------------------------------------------------------------------*/
+    #[view(getReferrers)]
+    fn get_referrers(&self) -> MultiValueEncoded<MultiValue2<ManagedAddress, ReferrerElement<Self::Api>>>
+    {
+        let mut t_result = MultiValueEncoded::new();
+        for (t_referrer_address, t_referrer) in self.referrers().iter() {
+            t_result.push((t_referrer_address, t_referrer).into());
+        }
+        t_result
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the lifetime earnings of a referrer, by token.                   *
+    *-------------------------------------------------------------------------*/
+    #[view(getReferrerEarnings)]
+    fn get_referrer_earnings(&self, p_referrer_address: ManagedAddress) -> MultiValueEncoded<MultiValue3<EgldOrEsdtTokenIdentifier, u64, BigUint>>
+    {
+        let mut t_result = MultiValueEncoded::new();
+        for ((t_token, t_nonce), t_amount) in self.referrer_earnings(&p_referrer_address).iter() {
+            t_result.push((t_token, t_nonce, t_amount).into());
+        }
+        t_result
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the beneficiaries the claimed fees are split among, and their    *
+    * shares in basis points.                                                  *
+    *-------------------------------------------------------------------------*/
+    #[view(getFeeBeneficiaries)]
+    fn get_fee_beneficiaries(&self) -> MultiValueEncoded<MultiValue2<ManagedAddress, u64>>
+    {
+        let mut t_result = MultiValueEncoded::new();
+        for (t_beneficiary_address, t_share) in self.fee_beneficiaries().iter() {
+            t_result.push((t_beneficiary_address, t_share).into());
+        }
+        t_result
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the address receiving the claimed fees: the configured fee       *
+    * recipient, or the owner of the contract if none is configured.           *
+    * Output:                                                                  *
+    * ManagedAddress representing the fee recipient.                           *
+    *-------------------------------------------------------------------------*/
+    #[view(getFeeRecipient)]
+    fn get_fee_recipient(&self)-> ManagedAddress
+    {
+        if self.fee_recipient().is_empty() {
+            return self.blockchain().get_owner_address();
+        }
+        self.fee_recipient().get()
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the discount tiers: the minimum balance of the discount token    *
+    * and the discount on the fee, in basis points.                            *
+    *-------------------------------------------------------------------------*/
+    #[view(getDiscountTiers)]
+    fn get_discount_tiers(&self) -> MultiValueEncoded<MultiValue2<BigUint, u64>>
+    {
+        let mut t_result = MultiValueEncoded::new();
+        for (t_min_balance, t_discount) in self.discount_tiers().iter() {
+            t_result.push((t_min_balance, t_discount).into());
+        }
+        t_result
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the discount on the fee an address gets when taking an offer, in *
+    * basis points: the best tier reached by its balance of the discount       *
+    * token, and whether the address is in the shard of the contract. The      *
+    * balance of an account in another shard cannot be read: it gets no        *
+    * discount, and frontends should not advertise one to it.                  *
+    *-------------------------------------------------------------------------*/
+    #[view(getFeeDiscount)]
+    fn get_fee_discount(&self, p_address: ManagedAddress) -> MultiValue2<u64, bool>
+    {
+        (self.compute_fee_discount(&p_address), self.is_same_shard(&p_address)).into()
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the price an offer can be taken at right now (the current point  *
+    * of the price decline for Dutch auction offers).                          *
+    *-------------------------------------------------------------------------*/
+    #[view(getCurrentPrice)]
+    fn get_current_price(&self, p_offer_id: u64) -> BigUint
+    {
+        let t_key: KeyElement<Self::Api> = match self.get_offer_key(p_offer_id) {
+            Some(t_key) => t_key,
+            None => sc_panic!("Offer not found."),
+        };
+        self.marketplace_elements().get(&t_key).unwrap().get_current_price(self.blockchain().get_block_timestamp())
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the royalties paid to the creators out of the current price of   *
+    * an offer (for everything listed, for unit price offers).                 *
+    *-------------------------------------------------------------------------*/
+    #[view(getOfferRoyalties)]
+    fn get_offer_royalties(&self, p_offer_id: u64) -> MultiValueEncoded<RoyaltyElement<Self::Api>>
+    {
+        let t_key: KeyElement<Self::Api> = match self.get_offer_key(p_offer_id) {
+            Some(t_key) => t_key,
+            None => sc_panic!("Offer not found."),
+        };
+        let t_marketplace_element: MarketplaceElement<Self::Api> = self.marketplace_elements().get(&t_key).unwrap();
+        let mut t_result = MultiValueEncoded::new();
+        if t_marketplace_element.is_barter() {
+            return t_result;
+        }
+        let t_price: BigUint = if t_marketplace_element.is_unit_price() {
+            t_marketplace_element.get_price() * &t_marketplace_element.get_items().get(0).amount
+        } else {
+            t_marketplace_element.get_current_price(self.blockchain().get_block_timestamp())
+        };
+        for t_royalty in self.get_royalties(t_marketplace_element.get_items(), &t_price).iter() {
+            t_result.push(t_royalty);
+        }
+        t_result
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the negotiation history (counter-offers and answers) of an offer.*
+    *-------------------------------------------------------------------------*/
+    #[view(getNegotiationHistory)]
+    fn get_negotiation_history(&self, p_offer_id: u64) -> MultiValueEncoded<NegotiationEntry<Self::Api>>
+    {
+        let mut t_result = MultiValueEncoded::new();
+        if let Some(t_key) = self.get_offer_key(p_offer_id) {
+            for t_entry in self.negotiation_history(&t_key).iter() {
+                t_result.push(t_entry);
+            }
+        }
+        t_result
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns all the open bids, with their ID.                                *
+    *-------------------------------------------------------------------------*/
+    #[view(getBids)]
+    fn get_bids(&self) -> MultiValueEncoded<MultiValue2<u64, BidElement<Self::Api>>>
+    {
+        let mut t_result = MultiValueEncoded::new();
+        for (t_bid_id, t_bid) in self.bids().iter() {
+            t_result.push((t_bid_id, t_bid).into());
+        }
+        t_result
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns all the auctions not settled yet, with their ID.                 *
+    *-------------------------------------------------------------------------*/
+    #[view(getAuctions)]
+    fn get_auctions(&self) -> MultiValueEncoded<MultiValue2<u64, AuctionElement<Self::Api>>>
+    {
+        let mut t_result = MultiValueEncoded::new();
+        for (t_auction_id, t_auction) in self.auctions().iter() {
+            t_result.push((t_auction_id, t_auction).into());
+        }
+        t_result
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns all the sealed-bid auctions not settled yet, with their ID.      *
+    *-------------------------------------------------------------------------*/
+    #[view(getSealedAuctions)]
+    fn get_sealed_auctions(&self) -> MultiValueEncoded<MultiValue2<u64, SealedAuctionElement<Self::Api>>>
+    {
+        let mut t_result = MultiValueEncoded::new();
+        for (t_auction_id, t_auction) in self.sealed_auctions().iter() {
+            t_result.push((t_auction_id, t_auction).into());
+        }
+        t_result
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the participants of a barter ring, in order, with the token      *
+    * each one must deposit and the tokens deposited so far (empty if not      *
+    * deposited yet).                                                          *
+    *-------------------------------------------------------------------------*/
+    #[view(getRing)]
+    fn get_ring(&self, p_ring_id: u64) -> MultiValueEncoded<MultiValue3<ManagedAddress, EsdtTokenPayment, ManagedVec<EsdtTokenPayment>>>
+    {
+        let mut t_result = MultiValueEncoded::new();
+        if let Some(t_ring) = self.rings().get(&p_ring_id) {
+            for (t_participant, t_expected_deposit) in t_ring.get_participants().iter().zip(t_ring.get_deposits().iter()) {
+                let t_items: ManagedVec<EsdtTokenPayment> = self.ring_deposits(p_ring_id).get(&t_participant).unwrap_or_default();
+                t_result.push((t_participant.clone_value(), t_expected_deposit, t_items).into());
+            }
+        }
+        t_result
+    }
+    /*-------------------------------------------------------------------------*
+    * Returns the hash to commit for a sealed bid: the sha256 of the address   *
+    * of the bidder, the bid amount (big endian, without leading zeros) and    *
+    * the salt. Binding the bidder keeps others from replaying the bid. The    *
+    * hash should be computed off-chain: querying this view through a public   *
+    * gateway discloses the bid and the salt.                                  *
+    *-------------------------------------------------------------------------*/
+    #[view(computeBidHash)]
+    fn compute_bid_hash(&self, p_bidder_address: ManagedAddress, p_bid: BigUint, p_salt: ManagedBuffer) -> ManagedByteArray<Self::Api, 32>
+    {
+        let mut t_data: ManagedBuffer = p_bidder_address.as_managed_buffer().clone();
+        t_data.append(&p_bid.to_bytes_be_buffer());
+        t_data.append(&p_salt);
+        self.crypto().sha256(t_data)
+    }
+    /*-------------------------------------------------------------------------*
+    *                                                                          *
+    *-------------------------------------------------------------------------*/
+
+    //Storage
+    /*-------------------------------------------------------------------------*
+    *  Unordered set to store all the marketplace elements.                    *
+    *-------------------------------------------------------------------------*/
+    //See storage mappers: https://docs.multiversx.com/developers/developer-reference/sc-annotations
+    #[storage_mapper("marketplaceElements")]
+    fn marketplace_elements(&self) -> MapMapper<KeyElement<Self::Api>, MarketplaceElement<Self::Api>>;
+    /*-------------------------------------------------------------------------*
+    *  Key of each listed offer, by ID.                                        *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("offerKey")]
+    fn offer_key(&self, p_offer_id: u64) -> SingleValueMapper<KeyElement<Self::Api>>;
+    /*-------------------------------------------------------------------------*
+    *  Last ID assigned to an offer.                                           *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("lastOfferId")]
+    fn last_offer_id(&self) -> SingleValueMapper<u64>;
+    /*-------------------------------------------------------------------------*
+    *  Counter-offers proposed by the taker of an offer, and their answers.    *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("negotiationHistory")]
+    fn negotiation_history(&self, p_key: &KeyElement<Self::Api>) -> VecMapper<NegotiationEntry<Self::Api>>;
+    /*-------------------------------------------------------------------------*
+    *  Bids (reverse offers), by ID.                                           *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("bids")]
+    fn bids(&self) -> MapMapper<u64, BidElement<Self::Api>>;
+    /*-------------------------------------------------------------------------*
+    *  Last ID assigned to a bid.                                              *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("lastBidId")]
+    fn last_bid_id(&self) -> SingleValueMapper<u64>;
+    /*-------------------------------------------------------------------------*
+    *  English auctions, by ID.                                                *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("auctions")]
+    fn auctions(&self) -> MapMapper<u64, AuctionElement<Self::Api>>;
+    /*-------------------------------------------------------------------------*
+    *  Last ID assigned to an auction.                                         *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("lastAuctionId")]
+    fn last_auction_id(&self) -> SingleValueMapper<u64>;
+    /*-------------------------------------------------------------------------*
+    *  Sealed-bid auctions, by ID.                                             *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("sealedAuctions")]
+    fn sealed_auctions(&self) -> MapMapper<u64, SealedAuctionElement<Self::Api>>;
+    /*-------------------------------------------------------------------------*
+    *  Last ID assigned to a sealed-bid auction.                               *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("lastSealedAuctionId")]
+    fn last_sealed_auction_id(&self) -> SingleValueMapper<u64>;
+    /*-------------------------------------------------------------------------*
+    *  Bids committed to a sealed-bid auction, by bidder.                      *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("sealedBids")]
+    fn sealed_bids(&self, p_auction_id: u64) -> MapMapper<ManagedAddress, SealedBid<Self::Api>>;
+    /*-------------------------------------------------------------------------*
+    *  Hashes committed to a sealed-bid auction, with the bidder of each.      *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("sealedBidHashes")]
+    fn sealed_bid_hashes(&self, p_auction_id: u64) -> MapMapper<ManagedByteArray<Self::Api, 32>, ManagedAddress>;
+    /*-------------------------------------------------------------------------*
+    *  Barter rings, by ID.                                                    *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("rings")]
+    fn rings(&self) -> MapMapper<u64, RingElement<Self::Api>>;
+    /*-------------------------------------------------------------------------*
+    *  Last ID assigned to a barter ring.                                      *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("lastRingId")]
+    fn last_ring_id(&self) -> SingleValueMapper<u64>;
+    /*-------------------------------------------------------------------------*
+    *  Tokens deposited in a barter ring, by participant.                      *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("ringDeposits")]
+    fn ring_deposits(&self, p_ring_id: u64) -> MapMapper<ManagedAddress, ManagedVec<EsdtTokenPayment>>;
+    /*-------------------------------------------------------------------------*
+    *  Fee charged when an offer is taken (empty if none).                     *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("fee")]
+    fn fee(&self) -> SingleValueMapper<FeeElement>;
+    /*-------------------------------------------------------------------------*
+    *  Minimum and maximum fee, by payment token (no caps if absent).          *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("feeCaps")]
+    fn fee_caps(&self) -> MapMapper<EgldOrEsdtTokenIdentifier, (BigUint, BigUint)>;
+    /*-------------------------------------------------------------------------*
+    *  Fee charged when an offer is created (empty if none).                   *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("listingFee")]
+    fn listing_fee(&self) -> SingleValueMapper<EsdtTokenPayment>;
+    /*-------------------------------------------------------------------------*
+    *  Address receiving the claimed fees (empty for the owner).               *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("feeRecipient")]
+    fn fee_recipient(&self) -> SingleValueMapper<ManagedAddress>;
+    /*-------------------------------------------------------------------------*
+    *  Token whose holders get a discount on the fee (empty if none).          *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("discountToken")]
+    fn discount_token(&self) -> SingleValueMapper<TokenIdentifier>;
+    /*-------------------------------------------------------------------------*
+    *  Discount on the fee in basis points, by minimum balance of the discount *
+    *  token.                                                                  *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("discountTiers")]
+    fn discount_tiers(&self) -> MapMapper<BigUint, u64>;
+    /*-------------------------------------------------------------------------*
+    *  Fees kept by the contract until they are claimed, by token and nonce.   *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("accruedFees")]
+    fn accrued_fees(&self) -> MapMapper<(EgldOrEsdtTokenIdentifier, u64), BigUint>;
+    /*-------------------------------------------------------------------------*
+    *  Share of the fee and caps of each approved referrer.                    *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("referrers")]
+    fn referrers(&self) -> MapMapper<ManagedAddress, ReferrerElement<Self::Api>>;
+    /*-------------------------------------------------------------------------*
+    *  Lifetime earnings of a referrer, by token and nonce.                    *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("referrerEarnings")]
+    fn referrer_earnings(&self, p_referrer_address: &ManagedAddress) -> MapMapper<(EgldOrEsdtTokenIdentifier, u64), BigUint>;
+    /*-------------------------------------------------------------------------*
+    *  Beneficiaries the claimed fees are split among, and their shares in     *
+    *  basis points (empty to send them to the fee recipient).                 *
+    *-------------------------------------------------------------------------*/
+    #[storage_mapper("feeBeneficiaries")]
+    fn fee_beneficiaries(&self) -> MapMapper<ManagedAddress, u64>;
 }
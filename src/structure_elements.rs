@@ -1,8 +1,8 @@
 multiversx_sc::imports!();
 multiversx_sc::derive_imports!();
 
-// A key containing the offerid, the bidder address and the taker address, that uniquely defines an offer
-#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+// A key containing the offerid, the bidder address and the taker address, that uniquely defines an offer. The taker address is the zero address for open offers
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, Clone)]
 pub struct KeyElement<M: ManagedTypeApi>
 {
     offer_id: u64,
@@ -29,41 +29,684 @@ impl<M: ManagedTypeApi> KeyElement<M> {
     }
     pub fn get_taker_address(&self)->&ManagedAddress<M>{
         &self.taker_address
-    }  
+    }
+    pub fn is_open(&self)->bool{
+        self.taker_address.is_zero()
+    }
 }
-// A marketplace element containing the token (collection id and nonce), the amount of the token, and the price (in EGLD) of this token
+// A marketplace element containing the bundle of tokens (collection id, nonce and amount of each one), the tokens wanted in exchange (barter offers only), the token the price is paid in (EGLD or ESDT, with its nonce), the price of the whole bundle (or of each unit of the single token listed, for unit price offers), the expiry timestamp (0 if it never expires), and for Dutch auction offers the floor price reached at the end of the linear price decline (the decline end is 0 for fixed price offers)
 #[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
 pub struct MarketplaceElement<M: ManagedTypeApi>
 {
-    collection_id: TokenIdentifier<M>,
-    nonce: u64,
-    amount: BigUint<M>,
+    items: ManagedVec<M, EsdtTokenPayment<M>>,
+    wanted_tokens: ManagedVec<M, EsdtTokenPayment<M>>,
+    payment_token: EgldOrEsdtTokenIdentifier<M>,
+    payment_nonce: u64,
     price: BigUint<M>,
+    unit_price: bool,
+    expires_at: u64,
+    floor_price: BigUint<M>,
+    decline_start: u64,
+    decline_end: u64,
 }
 impl<M: ManagedTypeApi> MarketplaceElement<M> {
     pub fn new(
-        collection_id: TokenIdentifier<M>,
-        nonce: u64,
-        amount: BigUint<M>,
+        items: ManagedVec<M, EsdtTokenPayment<M>>,
+        wanted_tokens: ManagedVec<M, EsdtTokenPayment<M>>,
+        payment_token: EgldOrEsdtTokenIdentifier<M>,
+        payment_nonce: u64,
         price: BigUint<M>,
+        unit_price: bool,
+        expires_at: u64,
     ) -> Self {
         MarketplaceElement {
-            collection_id,
-            nonce,
-            amount,
+            items,
+            wanted_tokens,
+            payment_token,
+            payment_nonce,
             price,
+            unit_price,
+            expires_at,
+            floor_price: BigUint::zero(),
+            decline_start: 0,
+            decline_end: 0,
         }
     }
-    pub fn get_collection_id(&self)->&TokenIdentifier<M>{
-        &self.collection_id
+    pub fn get_items(&self)->&ManagedVec<M, EsdtTokenPayment<M>>{
+        &self.items
     }
-    pub fn get_nonce(&self)->u64{
-        self.nonce
+    pub fn get_wanted_tokens(&self)->&ManagedVec<M, EsdtTokenPayment<M>>{
+        &self.wanted_tokens
     }
-    pub fn get_amount(&self)->&BigUint<M>{
-        &self.amount
+    pub fn is_barter(&self)->bool{
+        !self.wanted_tokens.is_empty()
+    }
+    pub fn get_payment_token(&self)->&EgldOrEsdtTokenIdentifier<M>{
+        &self.payment_token
+    }
+    pub fn get_payment_nonce(&self)->u64{
+        self.payment_nonce
     }
     pub fn get_price(&self)->&BigUint<M>{
         &self.price
-    }    
+    }
+    pub fn set_price(&mut self, price: BigUint<M>){
+        self.price = price;
+    }
+    pub fn is_unit_price(&self)->bool{
+        self.unit_price
+    }
+    // Adds a quantity of the token listed in a single token offer (top up)
+    pub fn add_amount(&mut self, quantity: &BigUint<M>){
+        let mut t_item = self.items.get(0);
+        t_item.amount += quantity;
+        self.items = ManagedVec::from_single_item(t_item);
+    }
+    // Removes a quantity of the token listed in a single token offer (partial fill or withdrawal)
+    pub fn sub_amount(&mut self, quantity: &BigUint<M>){
+        let mut t_item = self.items.get(0);
+        t_item.amount -= quantity;
+        self.items = ManagedVec::from_single_item(t_item);
+    }
+    pub fn get_expires_at(&self)->u64{
+        self.expires_at
+    }
+    pub fn is_expired(&self, timestamp: u64)->bool{
+        self.expires_at != 0 && timestamp >= self.expires_at
+    }
+    // Makes the price fall linearly to the floor price between two timestamps (Dutch auction)
+    pub fn set_price_decline(&mut self, floor_price: BigUint<M>, decline_start: u64, decline_end: u64){
+        self.floor_price = floor_price;
+        self.decline_start = decline_start;
+        self.decline_end = decline_end;
+    }
+    pub fn is_dutch_auction(&self)->bool{
+        self.decline_end != 0
+    }
+    pub fn get_floor_price(&self)->&BigUint<M>{
+        &self.floor_price
+    }
+    // The price to pay at a given time: the price itself for fixed price offers, or the point reached on the decline for Dutch auction offers
+    pub fn get_current_price(&self, timestamp: u64)->BigUint<M>{
+        if !self.is_dutch_auction() || timestamp <= self.decline_start {
+            return self.price.clone();
+        }
+        if timestamp >= self.decline_end {
+            return self.floor_price.clone();
+        }
+        let t_decline = (&self.price - &self.floor_price) * (timestamp - self.decline_start) / (self.decline_end - self.decline_start);
+        &self.price - &t_decline
+    }
+    pub fn get_status(&self, timestamp: u64)->OfferStatus{
+        if self.is_expired(timestamp) {
+            OfferStatus::Expired
+        } else {
+            OfferStatus::Active
+        }
+    }
+}
+// The status of an offer, as reported by the views
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, PartialEq, Eq, Clone, Copy)]
+pub enum OfferStatus
+{
+    Active,
+    Expired,
+}
+// An entry of the negotiation of an offer: the price proposed by the taker, when it was proposed, and the answer of the bidder
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct NegotiationEntry<M: ManagedTypeApi>
+{
+    proposer: ManagedAddress<M>,
+    price: BigUint<M>,
+    timestamp: u64,
+    status: NegotiationStatus,
+}
+impl<M: ManagedTypeApi> NegotiationEntry<M> {
+    pub fn new(
+        proposer: ManagedAddress<M>,
+        price: BigUint<M>,
+        timestamp: u64,
+        status: NegotiationStatus,
+    ) -> Self {
+        NegotiationEntry {
+            proposer,
+            price,
+            timestamp,
+            status,
+        }
+    }
+    pub fn get_proposer(&self)->&ManagedAddress<M>{
+        &self.proposer
+    }
+    pub fn get_price(&self)->&BigUint<M>{
+        &self.price
+    }
+    pub fn get_timestamp(&self)->u64{
+        self.timestamp
+    }
+    pub fn get_status(&self)->NegotiationStatus{
+        self.status
+    }
+    pub fn set_status(&mut self, status: NegotiationStatus){
+        self.status = status;
+    }
+}
+// The answer of the bidder to a counter-offer
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, PartialEq, Eq, Clone, Copy)]
+pub enum NegotiationStatus
+{
+    Proposed,
+    Accepted,
+    Rejected,
+}
+// A bid (reverse offer) containing the address of the bidder, the address of the holder asked to sell (the zero address if any holder can fill it), the token wanted (collection id, nonce and amount), the payment still locked in escrow by the bidder (EGLD or ESDT, with its nonce and amount), whether any nonce of the collection is accepted (collection bids), the number of items still wanted, each one paid an equal share of the payment, and the predicate the attributes of the items must satisfy (collection bids only)
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct BidElement<M: ManagedTypeApi>
+{
+    bidder_address: ManagedAddress<M>,
+    holder_address: ManagedAddress<M>,
+    wanted_token: EsdtTokenPayment<M>,
+    payment: EgldOrEsdtTokenPayment<M>,
+    collection: bool,
+    slots_left: u64,
+    attribute_match: AttributeMatch,
+    attribute_pattern: ManagedBuffer<M>,
+}
+impl<M: ManagedTypeApi> BidElement<M> {
+    pub fn new(
+        bidder_address: ManagedAddress<M>,
+        holder_address: ManagedAddress<M>,
+        wanted_token: EsdtTokenPayment<M>,
+        payment: EgldOrEsdtTokenPayment<M>,
+        collection: bool,
+        slots_left: u64,
+    ) -> Self {
+        BidElement {
+            bidder_address,
+            holder_address,
+            wanted_token,
+            payment,
+            collection,
+            slots_left,
+            attribute_match: AttributeMatch::Any,
+            attribute_pattern: ManagedBuffer::new(),
+        }
+    }
+    pub fn get_bidder_address(&self)->&ManagedAddress<M>{
+        &self.bidder_address
+    }
+    pub fn get_holder_address(&self)->&ManagedAddress<M>{
+        &self.holder_address
+    }
+    pub fn is_open(&self)->bool{
+        self.holder_address.is_zero()
+    }
+    pub fn get_wanted_token(&self)->&EsdtTokenPayment<M>{
+        &self.wanted_token
+    }
+    pub fn get_payment(&self)->&EgldOrEsdtTokenPayment<M>{
+        &self.payment
+    }
+    pub fn is_collection(&self)->bool{
+        self.collection
+    }
+    pub fn get_slots_left(&self)->u64{
+        self.slots_left
+    }
+    // Checks a token sent to fill the bid: the exact token wanted, or any nonce of the collection for collection bids
+    pub fn accepts(&self, token: &EsdtTokenPayment<M>)->bool{
+        let t_nonce_matches = if self.collection { token.token_nonce != 0 } else { token.token_nonce == self.wanted_token.token_nonce };
+        token.token_identifier == self.wanted_token.token_identifier && t_nonce_matches && token.amount == self.wanted_token.amount
+    }
+    pub fn set_attribute_filter(&mut self, attribute_match: AttributeMatch, attribute_pattern: ManagedBuffer<M>){
+        self.attribute_match = attribute_match;
+        self.attribute_pattern = attribute_pattern;
+    }
+    pub fn has_attribute_filter(&self)->bool{
+        self.attribute_match != AttributeMatch::Any
+    }
+    // Checks the attributes of a token sent to fill the bid against the predicate of the bid
+    pub fn attributes_match(&self, attributes: &ManagedBuffer<M>)->bool{
+        match self.attribute_match {
+            AttributeMatch::Any => true,
+            AttributeMatch::Contains => find_pattern(attributes, &self.attribute_pattern, false),
+            AttributeMatch::Entry => find_pattern(attributes, &self.attribute_pattern, true),
+        }
+    }
+    // Fills one slot, returning its share of the payment locked
+    pub fn fill_slot(&mut self)->BigUint<M>{
+        let t_slot_price = &self.payment.amount / self.slots_left;
+        self.payment.amount -= &t_slot_price;
+        self.slots_left -= 1;
+        t_slot_price
+    }
+}
+// The predicate the attributes of an NFT must satisfy to fill a collection bid: none (Any), containing the pattern anywhere (Contains), or having the pattern as one of their ';' separated entries, such as "tags:rare" in "metadata:cid;tags:rare" (Entry)
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi, PartialEq, Eq, Clone, Copy)]
+pub enum AttributeMatch
+{
+    Any,
+    Contains,
+    Entry,
+}
+// Looks for a pattern in a buffer, optionally only as a whole ';' separated entry. Works on managed buffers, without copying them to the heap
+fn find_pattern<M: ManagedTypeApi>(buffer: &ManagedBuffer<M>, pattern: &ManagedBuffer<M>, whole_entry: bool)->bool{
+    let t_len = buffer.len();
+    let t_pattern_len = pattern.len();
+    if t_pattern_len > t_len {
+        return false;
+    }
+    for t_start in 0..=(t_len - t_pattern_len) {
+        let t_end = t_start + t_pattern_len;
+        if whole_entry && !((t_start == 0 || byte_at(buffer, t_start - 1) == b';') && (t_end == t_len || byte_at(buffer, t_end) == b';')) {
+            continue;
+        }
+        if let Some(t_slice) = buffer.copy_slice(t_start, t_pattern_len) {
+            if t_slice == *pattern {
+                return true;
+            }
+        }
+    }
+    false
+}
+fn byte_at<M: ManagedTypeApi>(buffer: &ManagedBuffer<M>, index: usize)->u8{
+    let mut t_byte = [0u8; 1];
+    let _ = buffer.load_slice(index, &mut t_byte);
+    t_byte[0]
+}
+// An English auction containing the address of the seller, the bundle of tokens sold, the reserve price (in EGLD), the minimum increment between two bids, the end timestamp, and the highest bid escrowed so far with its bidder (the zero address if there is no bid yet)
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct AuctionElement<M: ManagedTypeApi>
+{
+    seller_address: ManagedAddress<M>,
+    items: ManagedVec<M, EsdtTokenPayment<M>>,
+    reserve_price: BigUint<M>,
+    min_increment: BigUint<M>,
+    end_at: u64,
+    highest_bidder: ManagedAddress<M>,
+    highest_bid: BigUint<M>,
+}
+impl<M: ManagedTypeApi> AuctionElement<M> {
+    pub fn new(
+        seller_address: ManagedAddress<M>,
+        items: ManagedVec<M, EsdtTokenPayment<M>>,
+        reserve_price: BigUint<M>,
+        min_increment: BigUint<M>,
+        end_at: u64,
+    ) -> Self {
+        AuctionElement {
+            seller_address,
+            items,
+            reserve_price,
+            min_increment,
+            end_at,
+            highest_bidder: ManagedAddress::zero(),
+            highest_bid: BigUint::zero(),
+        }
+    }
+    pub fn get_seller_address(&self)->&ManagedAddress<M>{
+        &self.seller_address
+    }
+    pub fn get_items(&self)->&ManagedVec<M, EsdtTokenPayment<M>>{
+        &self.items
+    }
+    pub fn get_end_at(&self)->u64{
+        self.end_at
+    }
+    pub fn has_ended(&self, timestamp: u64)->bool{
+        timestamp >= self.end_at
+    }
+    pub fn has_bid(&self)->bool{
+        !self.highest_bidder.is_zero()
+    }
+    pub fn get_highest_bidder(&self)->&ManagedAddress<M>{
+        &self.highest_bidder
+    }
+    pub fn get_highest_bid(&self)->&BigUint<M>{
+        &self.highest_bid
+    }
+    // The lowest bid accepted: the reserve price for the first bid, the highest bid plus the minimum increment afterwards
+    pub fn get_min_bid(&self)->BigUint<M>{
+        if self.has_bid() {
+            &self.highest_bid + &self.min_increment
+        } else {
+            self.reserve_price.clone()
+        }
+    }
+    pub fn set_highest_bid(&mut self, bidder: ManagedAddress<M>, bid: BigUint<M>){
+        self.highest_bidder = bidder;
+        self.highest_bid = bid;
+    }
+}
+// A sealed-bid auction containing the address of the seller, the bundle of tokens sold, the reserve price (in EGLD), the end of the commit phase, the end of the reveal phase, and the highest bid revealed so far with its bidder (the zero address if no valid bid has been revealed yet)
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct SealedAuctionElement<M: ManagedTypeApi>
+{
+    seller_address: ManagedAddress<M>,
+    items: ManagedVec<M, EsdtTokenPayment<M>>,
+    reserve_price: BigUint<M>,
+    commit_end: u64,
+    reveal_end: u64,
+    highest_bidder: ManagedAddress<M>,
+    highest_bid: BigUint<M>,
+}
+impl<M: ManagedTypeApi> SealedAuctionElement<M> {
+    pub fn new(
+        seller_address: ManagedAddress<M>,
+        items: ManagedVec<M, EsdtTokenPayment<M>>,
+        reserve_price: BigUint<M>,
+        commit_end: u64,
+        reveal_end: u64,
+    ) -> Self {
+        SealedAuctionElement {
+            seller_address,
+            items,
+            reserve_price,
+            commit_end,
+            reveal_end,
+            highest_bidder: ManagedAddress::zero(),
+            highest_bid: BigUint::zero(),
+        }
+    }
+    pub fn get_seller_address(&self)->&ManagedAddress<M>{
+        &self.seller_address
+    }
+    pub fn get_items(&self)->&ManagedVec<M, EsdtTokenPayment<M>>{
+        &self.items
+    }
+    pub fn get_reserve_price(&self)->&BigUint<M>{
+        &self.reserve_price
+    }
+    pub fn is_commit_phase(&self, timestamp: u64)->bool{
+        timestamp < self.commit_end
+    }
+    pub fn is_reveal_phase(&self, timestamp: u64)->bool{
+        timestamp >= self.commit_end && timestamp < self.reveal_end
+    }
+    pub fn has_ended(&self, timestamp: u64)->bool{
+        timestamp >= self.reveal_end
+    }
+    pub fn has_bid(&self)->bool{
+        !self.highest_bidder.is_zero()
+    }
+    pub fn get_highest_bidder(&self)->&ManagedAddress<M>{
+        &self.highest_bidder
+    }
+    pub fn get_highest_bid(&self)->&BigUint<M>{
+        &self.highest_bid
+    }
+    pub fn set_highest_bid(&mut self, bidder: ManagedAddress<M>, bid: BigUint<M>){
+        self.highest_bidder = bidder;
+        self.highest_bid = bid;
+    }
+}
+// A sealed bid containing the hash committed by the bidder (sha256 of the bidder address, the bid amount and a salt), the EGLD deposit hiding the bid, and whether the bid has been revealed
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct SealedBid<M: ManagedTypeApi>
+{
+    bid_hash: ManagedByteArray<M, 32>,
+    deposit: BigUint<M>,
+    revealed: bool,
+}
+impl<M: ManagedTypeApi> SealedBid<M> {
+    pub fn new(
+        bid_hash: ManagedByteArray<M, 32>,
+        deposit: BigUint<M>,
+    ) -> Self {
+        SealedBid {
+            bid_hash,
+            deposit,
+            revealed: false,
+        }
+    }
+    pub fn get_bid_hash(&self)->&ManagedByteArray<M, 32>{
+        &self.bid_hash
+    }
+    pub fn get_deposit(&self)->&BigUint<M>{
+        &self.deposit
+    }
+    pub fn is_revealed(&self)->bool{
+        self.revealed
+    }
+    pub fn set_revealed(&mut self){
+        self.revealed = true;
+    }
+}
+// A barter ring containing its participants, in order, and the tokens each one must deposit: the tokens deposited by each participant go to the next one, and those of the last participant go to the first one
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct RingElement<M: ManagedTypeApi>
+{
+    participants: ManagedVec<M, ManagedAddress<M>>,
+    deposits: ManagedVec<M, EsdtTokenPayment<M>>,
+}
+impl<M: ManagedTypeApi> RingElement<M> {
+    pub fn new(
+        participants: ManagedVec<M, ManagedAddress<M>>,
+        deposits: ManagedVec<M, EsdtTokenPayment<M>>,
+    ) -> Self {
+        RingElement {
+            participants,
+            deposits,
+        }
+    }
+    pub fn get_participants(&self)->&ManagedVec<M, ManagedAddress<M>>{
+        &self.participants
+    }
+    pub fn get_deposits(&self)->&ManagedVec<M, EsdtTokenPayment<M>>{
+        &self.deposits
+    }
+    pub fn is_participant(&self, address: &ManagedAddress<M>)->bool{
+        self.participants.contains(address)
+    }
+    // The token the given participant must deposit
+    pub fn get_expected_deposit(&self, address: &ManagedAddress<M>)->Option<EsdtTokenPayment<M>>{
+        let index = self.participants.iter().position(|participant| *participant == *address)?;
+        Some(self.deposits.get(index))
+    }
+    // The participant receiving the tokens deposited by the participant at the given index
+    pub fn get_receiver(&self, index: usize)->ManagedAddress<M>{
+        self.participants.get((index + 1) % self.participants.len()).clone_value()
+    }
+}
+// The royalties owed to the creator of a token sold through an offer, paid out of the price of the offer
+#[derive(ManagedVecItem, NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct RoyaltyElement<M: ManagedTypeApi>
+{
+    creator_address: ManagedAddress<M>,
+    amount: BigUint<M>,
+}
+impl<M: ManagedTypeApi> RoyaltyElement<M> {
+    pub fn new(
+        creator_address: ManagedAddress<M>,
+        amount: BigUint<M>,
+    ) -> Self {
+        RoyaltyElement {
+            creator_address,
+            amount,
+        }
+    }
+    pub fn get_creator_address(&self)->&ManagedAddress<M>{
+        &self.creator_address
+    }
+    pub fn get_amount(&self)->&BigUint<M>{
+        &self.amount
+    }
+}
+// The fee charged when an offer is taken: a share of the price in basis points, paid on top of the price by the buyer or taken from the proceeds of the seller. The minimum and maximum amounts depend on the payment token, and are given when computing the fee
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct FeeElement
+{
+    basis_points: u64,
+    paid_by_seller: bool,
+}
+impl FeeElement {
+    pub fn new(
+        basis_points: u64,
+        paid_by_seller: bool,
+    ) -> Self {
+        FeeElement {
+            basis_points,
+            paid_by_seller,
+        }
+    }
+    pub fn get_basis_points(&self)->u64{
+        self.basis_points
+    }
+    pub fn is_paid_by_seller(&self)->bool{
+        self.paid_by_seller
+    }
+    pub fn set_paid_by_seller(&mut self, paid_by_seller: bool){
+        self.paid_by_seller = paid_by_seller;
+    }
+    // The fee for a price, given the minimum and maximum amounts (0 for no maximum) and the denominator of the basis points
+    pub fn compute_fee<M: ManagedTypeApi>(&self, price: &BigUint<M>, min_fee: &BigUint<M>, max_fee: &BigUint<M>, denominator: u64)->BigUint<M>{
+        let fee = price * self.basis_points / denominator;
+        if fee < *min_fee {
+            return min_fee.clone();
+        }
+        if *max_fee > 0u32 && fee > *max_fee {
+            return max_fee.clone();
+        }
+        fee
+    }
+}
+// The best discount, in basis points, of the tiers (minimum balance, discount) reached by a balance. A tier with a discount of 0 counts as removed
+pub fn best_discount<M: ManagedTypeApi>(balance: &BigUint<M>, tiers: impl Iterator<Item = (BigUint<M>, u64)>)->u64{
+    let mut t_best_discount: u64 = 0;
+    for (t_min_balance, t_discount) in tiers {
+        if *balance >= t_min_balance && t_discount > t_best_discount {
+            t_best_discount = t_discount;
+        }
+    }
+    t_best_discount
+}
+// The most an approved referrer can earn over its lifetime in a payment token
+#[derive(ManagedVecItem, NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct ReferrerCapElement<M: ManagedTypeApi>
+{
+    token_identifier: EgldOrEsdtTokenIdentifier<M>,
+    token_nonce: u64,
+    amount: BigUint<M>,
+}
+impl<M: ManagedTypeApi> ReferrerCapElement<M> {
+    pub fn new(
+        token_identifier: EgldOrEsdtTokenIdentifier<M>,
+        token_nonce: u64,
+        amount: BigUint<M>,
+    ) -> Self {
+        ReferrerCapElement {
+            token_identifier,
+            token_nonce,
+            amount,
+        }
+    }
+    pub fn get_token_identifier(&self)->&EgldOrEsdtTokenIdentifier<M>{
+        &self.token_identifier
+    }
+    pub fn get_token_nonce(&self)->u64{
+        self.token_nonce
+    }
+    pub fn get_amount(&self)->&BigUint<M>{
+        &self.amount
+    }
+}
+// An approved referrer: its share of the fee in basis points, and the caps of its lifetime earnings (no ceiling for the other payment tokens)
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct ReferrerElement<M: ManagedTypeApi>
+{
+    share: u64,
+    caps: ManagedVec<M, ReferrerCapElement<M>>,
+}
+impl<M: ManagedTypeApi> ReferrerElement<M> {
+    pub fn new(
+        share: u64,
+        caps: ManagedVec<M, ReferrerCapElement<M>>,
+    ) -> Self {
+        ReferrerElement {
+            share,
+            caps,
+        }
+    }
+    pub fn get_share(&self)->u64{
+        self.share
+    }
+    pub fn get_caps(&self)->&ManagedVec<M, ReferrerCapElement<M>>{
+        &self.caps
+    }
+    // The lifetime ceiling of the earnings in the given token and nonce, if any
+    pub fn get_cap(&self, token: &EgldOrEsdtTokenIdentifier<M>, nonce: u64)->Option<BigUint<M>>{
+        self.caps.iter().find(|cap| cap.get_token_identifier() == token && cap.get_token_nonce() == nonce).map(|cap| cap.get_amount().clone())
+    }
+}
+// How the payment of a taken offer was split: the amount paid by the taker, the fee, the part of the fee sent to the referrer, the royalties paid to the creators and the proceeds sent to the bidder
+#[derive(NestedEncode, NestedDecode, TopEncode, TopDecode, TypeAbi)]
+pub struct SettlementElement<M: ManagedTypeApi>
+{
+    paid: BigUint<M>,
+    fee: BigUint<M>,
+    referral: BigUint<M>,
+    royalties: BigUint<M>,
+    proceeds: BigUint<M>,
+}
+impl<M: ManagedTypeApi> SettlementElement<M> {
+    pub fn new(
+        paid: BigUint<M>,
+        fee: BigUint<M>,
+        referral: BigUint<M>,
+        royalties: BigUint<M>,
+        proceeds: BigUint<M>,
+    ) -> Self {
+        SettlementElement {
+            paid,
+            fee,
+            referral,
+            royalties,
+            proceeds,
+        }
+    }
+    pub fn get_paid(&self)->&BigUint<M>{
+        &self.paid
+    }
+    pub fn get_fee(&self)->&BigUint<M>{
+        &self.fee
+    }
+    pub fn get_referral(&self)->&BigUint<M>{
+        &self.referral
+    }
+    pub fn get_royalties(&self)->&BigUint<M>{
+        &self.royalties
+    }
+    pub fn get_proceeds(&self)->&BigUint<M>{
+        &self.proceeds
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use multiversx_sc_scenario::api::StaticApi;
+
+    fn discount_of(balance: u64) -> u64 {
+        let t_tiers = [(100u64, 500u64), (1_000, 2_000), (10_000, 0)];
+        best_discount(&BigUint::<StaticApi>::from(balance), t_tiers.into_iter().map(|(t_min_balance, t_discount)| (BigUint::from(t_min_balance), t_discount)))
+    }
+    #[test]
+    fn best_discount_below_every_tier() {
+        assert_eq!(discount_of(0), 0);
+        assert_eq!(discount_of(99), 0);
+    }
+    #[test]
+    fn best_discount_between_tiers() {
+        assert_eq!(discount_of(100), 500);
+        assert_eq!(discount_of(999), 500);
+    }
+    #[test]
+    fn best_discount_best_tier() {
+        assert_eq!(discount_of(1_000), 2_000);
+    }
+    #[test]
+    fn best_discount_ignores_removed_tier() {
+        assert_eq!(discount_of(10_000), 2_000);
+        let t_removed = core::iter::once((BigUint::<StaticApi>::from(100u64), 0u64));
+        assert_eq!(best_discount(&BigUint::<StaticApi>::from(1_000u64), t_removed), 0);
+    }
 }
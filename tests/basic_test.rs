@@ -101,58 +101,4568 @@ fn deploy_step(p_owner_address: &str, p_code_expression: &BytesValue) -> ScDeplo
     .code(p_code_expression) //Can be set a code of value BytesValue
 }
 /*-------------------------------------------------------------------------*
+* Expects a successful call creating an offer, bid, auction or ring, and   *
+* returning the given id.                                                  *
+*-------------------------------------------------------------------------*/
+fn expect_new_id(p_id: u64) -> TxExpect
+{
+    TxExpect::ok().result(&p_id.to_string())
+}
+/*-------------------------------------------------------------------------*
+* Expects a successful takeOffer call returning the given settlement       *
+* breakdown (amount paid, fee, royalties and proceeds of the bidder),      *
+* without a referrer.                                                      *
+*-------------------------------------------------------------------------*/
+fn expect_settlement(p_paid: u64, p_fee: u64, p_royalties: u64, p_proceeds: u64) -> TxExpect
+{
+    expect_settlement_with_referral(p_paid, p_fee, 0, p_royalties, p_proceeds)
+}
+/*-------------------------------------------------------------------------*
+* Expects a successful takeOffer call returning the given settlement       *
+* breakdown, with the part of the fee sent to the referrer.                *
+*-------------------------------------------------------------------------*/
+fn expect_settlement_with_referral(p_paid: u64, p_fee: u64, p_referral: u64, p_royalties: u64, p_proceeds: u64) -> TxExpect
+{
+    TxExpect::ok().result(&format!("biguint:{}|biguint:{}|biguint:{}|biguint:{}|biguint:{}", p_paid, p_fee, p_referral, p_royalties, p_proceeds))
+}
+/*-------------------------------------------------------------------------*
 * Calls the createOffer function from the SC.                              *
 *-------------------------------------------------------------------------*/
-fn call_create_offer(p_test_data: &TestData, p_fee: u64) -> ScCallStep
-{
-    ScCallStep::new()
-    .from(AddressKey::from(&p_test_data.bidder_bech32)) //Address of the caller
-    .to("sc:exchangeplace") //destination of the call (the smart contract)
-    .esdt_transfer(BytesKey::from(p_test_data.str_token_id.clone().into_bytes()),p_test_data.nonce,BigUintValue::from(p_test_data.amount)) //Transfer the NFT or SFT
-    .egld_value(BigUintValue::from(p_fee)) //Pay the fee
-    .function("createOffer") //The name of the function
-    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
-    .argument(BytesValue::from(p_test_data.price.to_be_bytes().as_ref())) //Price of the offer
-    .argument(BytesValue::from(p_test_data.taker_bech32.as_bytes())) //Address of the taker
+fn call_create_offer(p_test_data: &TestData, p_fee: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //Address of the caller
+    .to("sc:exchangeplace") //destination of the call (the smart contract)
+    .esdt_transfer(BytesKey::from(p_test_data.str_token_id.clone().into_bytes()),p_test_data.nonce,BigUintValue::from(p_test_data.amount)) //Transfer the NFT or SFT
+    .egld_value(BigUintValue::from(p_fee)) //Pay the fee
+    .function("createOffer") //The name of the function
+    .argument(BytesValue::from(p_test_data.price.to_be_bytes().as_ref())) //Price of the offer
+    .argument(BytesValue::from(p_test_data.taker_bech32.as_bytes())) //Address of the taker
+}
+/*-------------------------------------------------------------------------*
+* Calls the takeOffer function from the SC.                                *
+*-------------------------------------------------------------------------*/
+fn call_take_offer(p_test_data: &TestData, p_fee: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.taker_bech32)) //AddressValue of the caller
+    .to("sc:exchangeplace") //destination of the call (the smart contract)
+    .egld_value(BigUintValue::from(p_test_data.price + p_fee))
+    .function("takeOffer") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
+}
+/*-------------------------------------------------------------------------*
+* Calls the createOffer function from the SC, pricing the offer in an ESDT.*
+*-------------------------------------------------------------------------*/
+fn call_create_offer_with_payment_token(p_test_data: &TestData, p_payment_token: &str, p_payment_nonce: u64) -> ScCallStep
+{
+    call_create_offer(p_test_data, 0)
+    .argument(BytesValue::from(p_payment_token.as_bytes())) //Token the offer is priced in
+    .argument(BytesValue::from(p_payment_nonce.to_be_bytes().as_ref())) //Nonce of the payment token
+}
+/*-------------------------------------------------------------------------*
+* Calls the takeOffer function from the SC, paying with an ESDT.           *
+*-------------------------------------------------------------------------*/
+fn call_take_offer_with_esdt(p_test_data: &TestData, p_payment_token: &str, p_payment_nonce: u64, p_fee: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.taker_bech32)) //AddressValue of the caller
+    .to("sc:exchangeplace") //destination of the call (the smart contract)
+    .esdt_transfer(BytesKey::from(p_payment_token.as_bytes().to_vec()),p_payment_nonce,BigUintValue::from(p_test_data.price + p_fee)) //Pay with the ESDT
+    .function("takeOffer") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
+}
+/*-------------------------------------------------------------------------*
+* Calls the createBarterOffer function from the SC, asking for a single    *
+* token (identifier, nonce, amount) in exchange.                           *
+*-------------------------------------------------------------------------*/
+fn call_create_barter_offer(p_test_data: &TestData, p_wanted_token: &str, p_wanted_nonce: u64, p_wanted_amount: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //Address of the caller
+    .to("sc:exchangeplace") //destination of the call (the smart contract)
+    .esdt_transfer(BytesKey::from(p_test_data.str_token_id.clone().into_bytes()),p_test_data.nonce,BigUintValue::from(p_test_data.amount)) //Transfer the NFT or SFT
+    .function("createBarterOffer") //The name of the function
+    .argument(BytesValue::from(p_test_data.taker_bech32.as_bytes())) //Address of the taker
+    .argument(format!("nested:str:{}|u64:{}|biguint:{}", p_wanted_token, p_wanted_nonce, p_wanted_amount).as_str()) //Wanted token (identifier, nonce, amount)
+}
+/*-------------------------------------------------------------------------*
+* Calls the createBarterOffer function from the SC, with an expiry         *
+* timestamp.                                                               *
+*-------------------------------------------------------------------------*/
+fn call_create_barter_offer_with_expiry(p_test_data: &TestData, p_wanted_token: &str, p_wanted_nonce: u64, p_wanted_amount: u64, p_expires_at: u64) -> ScCallStep
+{
+    call_create_barter_offer(p_test_data, p_wanted_token, p_wanted_nonce, p_wanted_amount)
+    .argument(BytesValue::from(p_expires_at.to_be_bytes().as_ref())) //Expiry timestamp
+}
+/*-------------------------------------------------------------------------*
+* Calls the createOffer function from the SC, with an expiry timestamp.    *
+*-------------------------------------------------------------------------*/
+fn call_create_offer_with_expiry(p_test_data: &TestData, p_expires_at: u64) -> ScCallStep
+{
+    call_create_offer_with_payment_token(p_test_data, "EGLD", 0)
+    .argument(BytesValue::from(p_expires_at.to_be_bytes().as_ref())) //Expiry timestamp
+}
+/*-------------------------------------------------------------------------*
+* Calls the reclaimExpiredOffer function from the SC.                      *
+*-------------------------------------------------------------------------*/
+fn call_reclaim_expired_offer(p_test_data: &TestData, p_caller: &Address) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(p_caller)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("reclaimExpiredOffer") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
+}
+/*-------------------------------------------------------------------------*
+* Calls the createOffer function from the SC, with a price per unit.       *
+*-------------------------------------------------------------------------*/
+fn call_create_unit_price_offer(p_test_data: &TestData) -> ScCallStep
+{
+    call_create_offer_with_expiry(p_test_data, 0)
+    .argument(BytesValue::from([1u8].as_ref())) //Price is per unit
+}
+/*-------------------------------------------------------------------------*
+* Calls the takeOffer function from the SC, buying only a quantity of a    *
+* unit price offer.                                                        *
+*-------------------------------------------------------------------------*/
+fn call_take_offer_quantity(p_test_data: &TestData, p_quantity: u64, p_fee: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.taker_bech32)) //AddressValue of the caller
+    .to("sc:exchangeplace") //destination of the call (the smart contract)
+    .egld_value(BigUintValue::from(p_test_data.price * p_quantity + p_fee))
+    .function("takeOffer") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
+    .argument(BytesValue::from(p_quantity.to_be_bytes().as_ref())) //Quantity to buy
+}
+/*-------------------------------------------------------------------------*
+* Calls the proposeCounter function from the SC.                           *
+*-------------------------------------------------------------------------*/
+fn call_propose_counter(p_test_data: &TestData, p_counter_price: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.taker_bech32)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("proposeCounter") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
+    .argument(BytesValue::from(p_counter_price.to_be_bytes().as_ref())) //Price proposed
+}
+/*-------------------------------------------------------------------------*
+* Calls the acceptCounter or rejectCounter function from the SC.           *
+*-------------------------------------------------------------------------*/
+fn call_answer_counter(p_test_data: &TestData, p_function: &str) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function(p_function) //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
+}
+/*-------------------------------------------------------------------------*
+* Calls the updateOfferPrice function from the SC.                         *
+*-------------------------------------------------------------------------*/
+fn call_update_offer_price(p_test_data: &TestData, p_new_price: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("updateOfferPrice") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
+    .argument(BytesValue::from(p_new_price.to_be_bytes().as_ref())) //New price
+}
+/*-------------------------------------------------------------------------*
+* Calls the takeOffer function from the SC, with the price the taker       *
+* expects to pay.                                                          *
+*-------------------------------------------------------------------------*/
+fn call_take_offer_expecting_price(p_test_data: &TestData, p_fee: u64) -> ScCallStep
+{
+    call_take_offer(p_test_data, p_fee)
+    .argument(BytesValue::from(0u64.to_be_bytes().as_ref())) //Whole offer
+    .argument(BytesValue::from(p_test_data.price.to_be_bytes().as_ref())) //Price the taker expects
+}
+/*-------------------------------------------------------------------------*
+* Calls the changeOfferTaker function from the SC.                         *
+*-------------------------------------------------------------------------*/
+fn call_change_offer_taker(p_test_data: &TestData, p_new_taker: &Address) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("changeOfferTaker") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
+    .argument(BytesValue::from(p_new_taker.as_bytes())) //Address of the new taker
+}
+/*-------------------------------------------------------------------------*
+* Calls the addToOffer function from the SC.                               *
+*-------------------------------------------------------------------------*/
+fn call_add_to_offer(p_test_data: &TestData, p_amount: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .esdt_transfer(BytesKey::from(p_test_data.str_token_id.clone().into_bytes()),p_test_data.nonce,BigUintValue::from(p_amount)) //Transfer the tokens to add
+    .function("addToOffer") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
+}
+/*-------------------------------------------------------------------------*
+* Calls the withdrawFromOffer function from the SC.                        *
+*-------------------------------------------------------------------------*/
+fn call_withdraw_from_offer(p_test_data: &TestData, p_amount: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("withdrawFromOffer") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
+    .argument(BytesValue::from(p_amount.to_be_bytes().as_ref())) //Amount to withdraw
+}
+/*-------------------------------------------------------------------------*
+* Calls the refundOffer function from the SC.                              *
+*-------------------------------------------------------------------------*/
+fn call_refund_offer(p_test_data: &TestData) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("refundOffer") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
+}
+/*-------------------------------------------------------------------------*
+* Calls the createDutchOffer function from the SC: the price of the offer  *
+* falls from the price of the test data to the floor price.                *
+*-------------------------------------------------------------------------*/
+fn call_create_dutch_offer(p_test_data: &TestData, p_floor_price: u64, p_decline_start: u64, p_decline_end: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .esdt_transfer(BytesKey::from(p_test_data.str_token_id.clone().into_bytes()),p_test_data.nonce,BigUintValue::from(p_test_data.amount)) //Transfer the NFT or SFT
+    .function("createDutchOffer") //The name of the function
+    .argument(BytesValue::from(p_test_data.taker_bech32.as_bytes())) //Address of the taker
+    .argument(BytesValue::from(p_test_data.price.to_be_bytes().as_ref())) //Start price
+    .argument(BytesValue::from(p_floor_price.to_be_bytes().as_ref())) //Floor price
+    .argument(BytesValue::from(p_decline_start.to_be_bytes().as_ref())) //Start of the price decline
+    .argument(BytesValue::from(p_decline_end.to_be_bytes().as_ref())) //End of the price decline
+}
+/*-------------------------------------------------------------------------*
+* Calls the createDutchOffer function from the SC, priced in EGLD, with an *
+* expiry timestamp.                                                        *
+*-------------------------------------------------------------------------*/
+fn call_create_dutch_offer_with_expiry(p_test_data: &TestData, p_floor_price: u64, p_decline_start: u64, p_decline_end: u64, p_expires_at: u64) -> ScCallStep
+{
+    call_create_dutch_offer(p_test_data, p_floor_price, p_decline_start, p_decline_end)
+    .argument(BytesValue::from("EGLD".as_bytes())) //Payment token
+    .argument(BytesValue::from(0u64.to_be_bytes().as_ref())) //Nonce of the payment token
+    .argument(BytesValue::from(p_expires_at.to_be_bytes().as_ref())) //Expiry timestamp
+}
+/*-------------------------------------------------------------------------*
+* Calls the createBid function from the SC: the bidder locks the price in  *
+* EGLD to buy the token from the taker (the holder).                       *
+*-------------------------------------------------------------------------*/
+fn call_create_bid(p_test_data: &TestData) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .egld_value(BigUintValue::from(p_test_data.price)) //Lock the price
+    .function("createBid") //The name of the function
+    .argument(BytesValue::from(p_test_data.taker_bech32.as_bytes())) //Address of the holder
+    .argument(BytesValue::from(p_test_data.str_token_id.as_bytes())) //Token wanted
+    .argument(BytesValue::from(p_test_data.nonce.to_be_bytes().as_ref())) //Nonce of the token wanted
+    .argument(BytesValue::from(p_test_data.amount.to_be_bytes().as_ref())) //Amount of the token wanted
+}
+/*-------------------------------------------------------------------------*
+* Calls the acceptBid function from the SC, sending the given nonce of the *
+* token wanted.                                                            *
+*-------------------------------------------------------------------------*/
+fn call_accept_bid(p_test_data: &TestData, p_nonce: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.taker_bech32)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .esdt_transfer(BytesKey::from(p_test_data.str_token_id.clone().into_bytes()),p_nonce,BigUintValue::from(p_test_data.amount)) //Send the token
+    .function("acceptBid") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the bid
+}
+/*-------------------------------------------------------------------------*
+* Calls the createCollectionBid function from the SC: the bidder locks the *
+* price in EGLD to buy a number of items of the collection.                *
+*-------------------------------------------------------------------------*/
+fn call_create_collection_bid(p_test_data: &TestData, p_slots: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .egld_value(BigUintValue::from(p_test_data.price)) //Lock the price of all the items
+    .function("createCollectionBid") //The name of the function
+    .argument(BytesValue::from(p_test_data.str_token_id.as_bytes())) //Collection wanted
+    .argument(BytesValue::from(p_slots.to_be_bytes().as_ref())) //Number of items wanted
+}
+/*-------------------------------------------------------------------------*
+* Calls the createCollectionBid function from the SC, with a predicate on  *
+* the attributes of the items (1: Contains, 2: Entry).                     *
+*-------------------------------------------------------------------------*/
+fn call_create_attribute_bid(p_test_data: &TestData, p_slots: u64, p_attribute_match: u8, p_attribute_pattern: &str) -> ScCallStep
+{
+    call_create_collection_bid(p_test_data, p_slots)
+    .argument(BytesValue::from([p_attribute_match].as_ref())) //Predicate on the attributes
+    .argument(BytesValue::from(p_attribute_pattern.as_bytes())) //Pattern of the predicate
+}
+/*-------------------------------------------------------------------------*
+* Calls the cancelBid function from the SC.                                *
+*-------------------------------------------------------------------------*/
+fn call_cancel_bid(p_test_data: &TestData) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("cancelBid") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the bid
+}
+/*-------------------------------------------------------------------------*
+* Calls the createAuction function from the SC: the bidder of the test     *
+* data sells the token, with the price of the test data as reserve price.  *
+*-------------------------------------------------------------------------*/
+fn call_create_auction(p_test_data: &TestData, p_min_increment: u64, p_end_at: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .esdt_transfer(BytesKey::from(p_test_data.str_token_id.clone().into_bytes()),p_test_data.nonce,BigUintValue::from(p_test_data.amount)) //Transfer the NFT or SFT
+    .function("createAuction") //The name of the function
+    .argument(BytesValue::from(p_test_data.price.to_be_bytes().as_ref())) //Reserve price
+    .argument(BytesValue::from(p_min_increment.to_be_bytes().as_ref())) //Minimum increment
+    .argument(BytesValue::from(p_end_at.to_be_bytes().as_ref())) //End timestamp
+}
+/*-------------------------------------------------------------------------*
+* Calls the bidOnAuction function from the SC.                             *
+*-------------------------------------------------------------------------*/
+fn call_bid_on_auction(p_test_data: &TestData, p_caller: &Address, p_bid: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(p_caller)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .egld_value(BigUintValue::from(p_bid)) //Escrow the bid
+    .function("bidOnAuction") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the auction
+}
+/*-------------------------------------------------------------------------*
+* Calls the settleAuction function from the SC.                            *
+*-------------------------------------------------------------------------*/
+fn call_settle_auction(p_test_data: &TestData, p_caller: &Address) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(p_caller)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("settleAuction") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the auction
+}
+/*-------------------------------------------------------------------------*
+* Computes the hash committed for a sealed bid: the sha256 of the address  *
+* of the bidder, the bid (big endian, without leading zeros) and the salt. *
+*-------------------------------------------------------------------------*/
+fn sealed_bid_hash(p_bidder: &Address, p_bid: u64, p_salt: &str) -> Vec<u8>
+{
+    let mut t_data: Vec<u8> = p_bidder.as_bytes().to_vec();
+    t_data.extend(p_bid.to_be_bytes().iter().skip_while(|t_byte| **t_byte == 0));
+    t_data.extend_from_slice(p_salt.as_bytes());
+    multiversx_sc_scenario::multiversx_chain_vm::crypto_functions::sha256(&t_data).to_vec()
+}
+/*-------------------------------------------------------------------------*
+* Calls the createSealedAuction function from the SC: the bidder of the    *
+* test data sells the token, with the price of the test data as reserve    *
+* price.                                                                   *
+*-------------------------------------------------------------------------*/
+fn call_create_sealed_auction(p_test_data: &TestData, p_commit_end: u64, p_reveal_end: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .esdt_transfer(BytesKey::from(p_test_data.str_token_id.clone().into_bytes()),p_test_data.nonce,BigUintValue::from(p_test_data.amount)) //Transfer the NFT or SFT
+    .function("createSealedAuction") //The name of the function
+    .argument(BytesValue::from(p_test_data.price.to_be_bytes().as_ref())) //Reserve price
+    .argument(BytesValue::from(p_commit_end.to_be_bytes().as_ref())) //End of the commit phase
+    .argument(BytesValue::from(p_reveal_end.to_be_bytes().as_ref())) //End of the reveal phase
+}
+/*-------------------------------------------------------------------------*
+* Calls the commitSealedBid function from the SC.                          *
+*-------------------------------------------------------------------------*/
+fn call_commit_sealed_bid(p_test_data: &TestData, p_caller: &Address, p_bid: u64, p_salt: &str, p_deposit: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(p_caller)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .egld_value(BigUintValue::from(p_deposit)) //Escrow the deposit
+    .function("commitSealedBid") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the auction
+    .argument(BytesValue::from(sealed_bid_hash(p_caller, p_bid, p_salt))) //Hash of the bid
+}
+/*-------------------------------------------------------------------------*
+* Calls the revealSealedBid function from the SC.                          *
+*-------------------------------------------------------------------------*/
+fn call_reveal_sealed_bid(p_test_data: &TestData, p_caller: &Address, p_bid: u64, p_salt: &str) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(p_caller)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("revealSealedBid") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the auction
+    .argument(BytesValue::from(p_bid.to_be_bytes().as_ref())) //Bid
+    .argument(BytesValue::from(p_salt.as_bytes())) //Salt
+}
+/*-------------------------------------------------------------------------*
+* Calls the settleSealedAuction function from the SC.                      *
+*-------------------------------------------------------------------------*/
+fn call_settle_sealed_auction(p_test_data: &TestData, p_caller: &Address) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(p_caller)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("settleSealedAuction") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the auction
+}
+/*-------------------------------------------------------------------------*
+* Calls the withdrawSealedDeposit function from the SC.                    *
+*-------------------------------------------------------------------------*/
+fn call_withdraw_sealed_deposit(p_test_data: &TestData, p_caller: &Address) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(p_caller)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("withdrawSealedDeposit") //The name of the function
+    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the auction
+}
+/*-------------------------------------------------------------------------*
+* Calls the createRing function from the SC. Each leg is a participant     *
+* and the token (identifier, nonce, amount) it must deposit.               *
+*-------------------------------------------------------------------------*/
+fn call_create_ring(p_caller: &Address, p_legs: &[(&Address, &str, u64, u64)]) -> ScCallStep
+{
+    let mut t_step = ScCallStep::new()
+    .from(AddressKey::from(p_caller)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("createRing"); //The name of the function
+    for (t_participant, t_str_token_id, t_nonce, t_amount) in p_legs {
+        t_step = t_step
+        .argument(BytesValue::from(t_participant.as_bytes())) //Address of the participant
+        .argument(BytesValue::from(t_str_token_id.as_bytes())) //Token it must deposit
+        .argument(BytesValue::from(t_nonce.to_be_bytes().as_ref())) //Nonce of the token
+        .argument(BytesValue::from(t_amount.to_be_bytes().as_ref())); //Amount of the token
+    }
+    t_step
+}
+/*-------------------------------------------------------------------------*
+* Calls the fundRing function from the SC, depositing one token.           *
+*-------------------------------------------------------------------------*/
+fn call_fund_ring(p_caller: &Address, p_ring_id: u64, p_str_token_id: &str, p_nonce: u64, p_amount: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(p_caller)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .esdt_transfer(BytesKey::from(p_str_token_id.as_bytes().to_vec()),p_nonce,BigUintValue::from(p_amount)) //Deposit the token
+    .function("fundRing") //The name of the function
+    .argument(BytesValue::from(p_ring_id.to_be_bytes().as_ref())) //ID of the ring
+}
+/*-------------------------------------------------------------------------*
+* Calls the cancelRing function from the SC.                               *
+*-------------------------------------------------------------------------*/
+fn call_cancel_ring(p_caller: &Address, p_ring_id: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(p_caller)) //AddressValue of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("cancelRing") //The name of the function
+    .argument(BytesValue::from(p_ring_id.to_be_bytes().as_ref())) //ID of the ring
+}
+/*-------------------------------------------------------------------------*
+* Calls the setFee function from the SC.                                   *
+*-------------------------------------------------------------------------*/
+fn call_set_fee(p_caller: &str, p_basis_points: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(p_caller) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("setFee") //The name of the function
+    .argument(BytesValue::from(p_basis_points.to_be_bytes().as_ref())) //Fee in basis points of the price
+}
+/*-------------------------------------------------------------------------*
+* Calls the setFeeCaps function from the SC.                               *
+*-------------------------------------------------------------------------*/
+fn call_set_fee_caps(p_caller: &str, p_token: &str, p_min_fee: u64, p_max_fee: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(p_caller) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("setFeeCaps") //The name of the function
+    .argument(BytesValue::from(p_token.as_bytes())) //Payment token
+    .argument(BytesValue::from(p_min_fee.to_be_bytes().as_ref())) //Minimum fee
+    .argument(BytesValue::from(p_max_fee.to_be_bytes().as_ref())) //Maximum fee (0 for no maximum)
+}
+/*-------------------------------------------------------------------------*
+* Calls the setFeePaidBySeller function from the SC.                       *
+*-------------------------------------------------------------------------*/
+fn call_set_fee_paid_by_seller(p_caller: &str, p_paid_by_seller: bool) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(p_caller) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("setFeePaidBySeller") //The name of the function
+    .argument(BytesValue::from([p_paid_by_seller as u8].as_ref())) //Whether the seller pays the fee
+}
+/*-------------------------------------------------------------------------*
+* Calls the setListingFee function from the SC.                            *
+*-------------------------------------------------------------------------*/
+fn call_set_listing_fee(p_caller: &str, p_str_token_id: &str, p_amount: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(p_caller) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("setListingFee") //The name of the function
+    .argument(BytesValue::from(p_str_token_id.as_bytes())) //Token of the listing fee
+    .argument(BytesValue::from(p_amount.to_be_bytes().as_ref())) //Listing fee
+}
+/*-------------------------------------------------------------------------*
+* Calls the setFeeRecipient function from the SC.                          *
+*-------------------------------------------------------------------------*/
+fn call_set_fee_recipient(p_caller: &str, p_recipient: &Address) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(p_caller) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("setFeeRecipient") //The name of the function
+    .argument(BytesValue::from(p_recipient.as_bytes())) //Address receiving the fees
+}
+/*-------------------------------------------------------------------------*
+* Calls the setDiscountToken function from the SC.                         *
+*-------------------------------------------------------------------------*/
+fn call_set_discount_token(p_caller: &str, p_str_token_id: &str) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(p_caller) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("setDiscountToken") //The name of the function
+    .argument(BytesValue::from(p_str_token_id.as_bytes())) //Token giving a discount on the fee
+}
+/*-------------------------------------------------------------------------*
+* Calls the setDiscountTier function from the SC.                          *
+*-------------------------------------------------------------------------*/
+fn call_set_discount_tier(p_caller: &str, p_min_balance: u64, p_discount: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(p_caller) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("setDiscountTier") //The name of the function
+    .argument(BytesValue::from(p_min_balance.to_be_bytes().as_ref())) //Minimum balance of the tier
+    .argument(BytesValue::from(p_discount.to_be_bytes().as_ref())) //Discount in basis points of the fee
+}
+/*-------------------------------------------------------------------------*
+* Calls the setFeeBeneficiaries function from the SC, with the address     *
+* expressions of the beneficiaries and their shares.                       *
+*-------------------------------------------------------------------------*/
+fn call_set_fee_beneficiaries(p_caller: &str, p_beneficiaries: &[(&str, u64)]) -> ScCallStep
+{
+    let mut t_step = ScCallStep::new()
+    .from(p_caller) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("setFeeBeneficiaries"); //The name of the function
+    for (t_beneficiary, t_share) in p_beneficiaries {
+        t_step = t_step
+        .argument(*t_beneficiary) //Address of the beneficiary
+        .argument(BytesValue::from(t_share.to_be_bytes().as_ref())); //Share of the beneficiary in basis points
+    }
+    t_step
+}
+/*-------------------------------------------------------------------------*
+* Calls the claimFees function from the SC, sending the fees to the fee    *
+* recipient.                                                               *
+*-------------------------------------------------------------------------*/
+fn call_claim_fees(p_caller: &str) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(p_caller) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("claimFees") //The name of the function
+}
+/*-------------------------------------------------------------------------*
+* Calls the claimFees function from the SC, sending the fees to the given  *
+* address.                                                                 *
+*-------------------------------------------------------------------------*/
+fn call_claim_fees_to(p_caller: &str, p_recipient: &Address) -> ScCallStep
+{
+    call_claim_fees(p_caller)
+    .argument(BytesValue::from(p_recipient.as_bytes())) //Address receiving the fees
+}
+/*-------------------------------------------------------------------------*
+* Calls the claimFees function from the SC, claiming the fees of the given *
+* tokens (identifier, nonce) only. The zero address sends them to the fee  *
+* recipient or the beneficiaries.                                          *
+*-------------------------------------------------------------------------*/
+fn call_claim_fees_of(p_caller: &str, p_recipient: &Address, p_tokens: &[(&str, u64)]) -> ScCallStep
+{
+    let mut t_step: ScCallStep = call_claim_fees_to(p_caller, p_recipient);
+    for (t_str_token_id, t_nonce) in p_tokens {
+        t_step = t_step
+        .argument(BytesValue::from(t_str_token_id.as_bytes())) //Token of the fees
+        .argument(BytesValue::from(t_nonce.to_be_bytes().as_ref())); //Nonce of the token
+    }
+    t_step
+}
+/*-------------------------------------------------------------------------*
+* Calls the approveReferrer function from the SC.                          *
+*-------------------------------------------------------------------------*/
+fn call_approve_referrer(p_caller: &str, p_referrer: &Address, p_share: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(p_caller) //Address of the caller
+    .to("sc:exchangeplace") //Destination of the call (the smart contract)
+    .function("approveReferrer") //The name of the function
+    .argument(BytesValue::from(p_referrer.as_bytes())) //Address of the referrer
+    .argument(BytesValue::from(p_share.to_be_bytes().as_ref())) //Share of the fee in basis points
+}
+/*-------------------------------------------------------------------------*
+* Calls the approveReferrer function from the SC, capping the lifetime     *
+* earnings of the referrer in a token.                                     *
+*-------------------------------------------------------------------------*/
+fn call_approve_referrer_with_cap(p_caller: &str, p_referrer: &Address, p_share: u64, p_cap_token: &str, p_cap: u64) -> ScCallStep
+{
+    call_approve_referrer(p_caller, p_referrer, p_share)
+    .argument(BytesValue::from(p_cap_token.as_bytes())) //Token of the cap
+    .argument(BytesValue::from(0u64.to_be_bytes().as_ref())) //Nonce of the token
+    .argument(BytesValue::from(p_cap.to_be_bytes().as_ref())) //Most the referrer can earn in the token
+}
+/*-------------------------------------------------------------------------*
+* Calls the takeOffer function from the SC through a referrer.             *
+*-------------------------------------------------------------------------*/
+fn call_take_offer_with_referrer(p_test_data: &TestData, p_fee: u64, p_referrer: &Address) -> ScCallStep
+{
+    call_take_offer_expecting_price(p_test_data, p_fee)
+    .argument(BytesValue::from(p_referrer.as_bytes())) //Address of the referrer
+}
+/*-------------------------------------------------------------------------*
+* Calls the createOffer function from the SC, paying the listing fee as    *
+* the last token of the transfer.                                          *
+*-------------------------------------------------------------------------*/
+fn call_create_offer_with_listing_fee(p_test_data: &TestData, p_fee_token_id: &str, p_listing_fee: u64) -> ScCallStep
+{
+    ScCallStep::new()
+    .from(AddressKey::from(&p_test_data.bidder_bech32)) //Address of the caller
+    .to("sc:exchangeplace") //destination of the call (the smart contract)
+    .esdt_transfer(BytesKey::from(p_test_data.str_token_id.clone().into_bytes()),p_test_data.nonce,BigUintValue::from(p_test_data.amount)) //Transfer the NFT or SFT
+    .esdt_transfer(BytesKey::from(p_fee_token_id.as_bytes().to_vec()),0,BigUintValue::from(p_listing_fee)) //Pay the listing fee
+    .function("createOffer") //The name of the function
+    .argument(BytesValue::from(p_test_data.price.to_be_bytes().as_ref())) //Price of the offer
+    .argument(BytesValue::from(p_test_data.taker_bech32.as_bytes())) //Address of the taker
+}
+/*-------------------------------------------------------------------------*
+* Initialization test: deploy the contract.                                *
+*-------------------------------------------------------------------------*/
+#[test]
+fn init_unit_test() {
+    let t_exchanger = barterswap::contract_obj::<SingleTxApi>();
+    t_exchanger.init();
+}
+/*-------------------------------------------------------------------------*
+* Create an offer and then take it.                                        *
+*-------------------------------------------------------------------------*/
+#[test]
+fn create_and_take_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call(
+            call_take_offer(&t_test_data,M_FEE).expect(expect_settlement(t_test_data.price + M_FEE, M_FEE, 0, t_test_data.price))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Place an offer with a huge price.                                        *
+*-------------------------------------------------------------------------*/
+#[test]
+fn create_offer_max_price_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = u64::MAX;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         );
+}
+/*-------------------------------------------------------------------------*
+* Place an offer with a huge ID.                                           *
+*-------------------------------------------------------------------------*/
+#[test]
+fn create_offer_max_nonce_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = u64::MAX;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         );
+}
+/*-------------------------------------------------------------------------*
+* Create two offers for the same taker: each one gets its own id, and the  *
+* second one can be taken on its own.                                      *
+*-------------------------------------------------------------------------*/
+#[test]
+fn create_offer_assigned_ids_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id_1 : &str = "PROPO-123456";
+    let t_str_token_id_2 : &str = "PROPO-179101";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data_1 = TestData::new(t_str_token_id_1, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_test_data_2 = TestData::new(t_str_token_id_2, t_nonce, t_amount, t_price, t_offer_id + 1, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id_1.as_bytes().to_vec()),BigUintValue::from(t_amount)).esdt_balance(BytesKey::from(t_str_token_id_2.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price)));        
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data_1,M_FEE).expect(expect_new_id(t_test_data_1.offer_id))
+         )
+        .sc_call(
+            call_create_offer(&t_test_data_2,M_FEE).expect(expect_new_id(t_test_data_2.offer_id))
+        )
+        .sc_call(
+            call_take_offer(&t_test_data_2,M_FEE).expect(expect_settlement(t_test_data_2.price + M_FEE, M_FEE, 0, t_test_data_2.price))
+        )
+        .check_state_step( //Only the second offer has been taken
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data_2.taker_bech32), CheckAccount::new().esdt_balance(BytesKey::from(t_str_token_id_2.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+            .put_account("sc:exchangeplace", CheckAccount::new().esdt_balance(BytesKey::from(t_str_token_id_1.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* The bidder places a bid, and the taker asks for a refund on an offer     *
+* that he didn't make (bad bidder address).                                *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Refund offer not found.")]
+fn refund_offer_bad_address_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_refund_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_taker_address, t_taker_address); //TAKER makes the call on an offer he didn't create: SHOULD PANIC
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0));        
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call(
+            call_refund_offer(&t_refund_data)
+        );
+}
+/*-------------------------------------------------------------------------*
+* The bidder places a bid, and the taker asks for a refund on an offer     *
+* that doesn't exist (bad offer ID).                                       *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Refund offer not found.")]
+fn refund_offer_bad_offerid_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_refund_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id + 1, t_bidder_address, t_taker_address); //Calls with offerid + 1, an offer that doesn't exist
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0));        
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call(
+            call_refund_offer(&t_refund_data)
+        );
+}
+/*-------------------------------------------------------------------------*
+* The bidder places a bid, and the taker asks to take an offer with an     *
+* unexistant offer id.                                                     *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Take offer not found.")]
+fn take_offer_bad_offerid_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+    let t_offer_id_nonexistant: u64 = 2;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id_nonexistant, t_bidder_address, t_taker_address); //ID of the offer (unexistant one, should panic)
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call(
+            call_take_offer(&t_taker_data,M_FEE)
+        );
+}
+/*-------------------------------------------------------------------------*
+* Try to take an offer made to someone else.                               *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Take offer not found.")]
+fn take_offer_bad_address_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_other_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_other_address); //Another address tries to take the offer. Generates error
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)))
+    .put_account(AddressKey::from(&bech32::decode(t_other_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call(
+            call_take_offer(&t_taker_data,M_FEE)
+        );
+}
+/*-------------------------------------------------------------------------*
+* Take an offer, but provided an incorrect payment in EGLD.                *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Incorrect payment provided.")]
+fn take_offer_invalid_payment_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price - 1, t_offer_id, t_bidder_address, t_taker_address); //Provide EGLD quantity - 1 (so not ENOUGH, PANIC)
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call(
+            call_take_offer(&t_taker_data,M_FEE)
+        );
+}
+/*-------------------------------------------------------------------------*
+* Try to place an offer from a smart contract address (bidder). Normally   *
+* the contract is deployed as Non-payable by smart contract, so this is for*
+* pure testing.                                                            *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Bidder address is from a smart contract.")]
+fn create_offer_bidder_sc_address_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1qqqqqqqqqqqqqpgq5cfxcvq5dqp290j2q9gw5yc8fcremmlqplkqtly3rs";
+    
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).code(EXCHANGE_PLACE_PATH_EXPR).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         );
+}
+/*-------------------------------------------------------------------------*
+* Try to place an offer for a smart contract address (taker). Normally the *
+* contract is deployed as Non-payable by smart contract, so this is for    *
+* pure testing.                                                            *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Taker address is from a smart contract.")]
+fn create_offer_taker_sc_address_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd1qqqqqqqqqqqqqpgq5cfxcvq5dqp290j2q9gw5yc8fcremmlqplkqtly3rs";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).code(EXCHANGE_PLACE_PATH_EXPR).balance(BigUintValue::from(t_price + M_FEE)));        
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         );
+}
+/*-------------------------------------------------------------------------*
+* Create an offer and then take it.                                        *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "No tokens provided for the offer.")]
+fn create_and_take_offer_no_nft_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            ScCallStep::new()
+                .from(AddressKey::from(&t_test_data.bidder_bech32)) //Address of the caller
+                .to("sc:exchangeplace") //destination of the call (the smart contract)
+                //.esdt_transfer(BytesKey::from(t_test_data.str_token_id.clone().into_bytes()),t_test_data.nonce,BigUintValue::from(t_test_data.amount)) //Don't transfer the NFT or SFT
+                .egld_value(BigUintValue::from(M_FEE)) //Pay the fee
+                .function("createOffer") //The name of the function
+                .argument(BytesValue::from(t_test_data.price.to_be_bytes().as_ref())) //Price of the offer
+                .argument(BytesValue::from(t_test_data.taker_bech32.as_bytes())) //Address of the taker.expect(TxExpect::ok().no_result())
+            )
+        .sc_call(
+            call_take_offer(&t_test_data,M_FEE).expect(expect_settlement(t_test_data.price + M_FEE, M_FEE, 0, t_test_data.price))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Simultaneous call for taking and refunding an offer.                     *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Refund offer not found.")]
+fn create_and_take_and_refund_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call(
+            call_take_offer(&t_test_data,M_FEE).expect(expect_settlement(t_test_data.price + M_FEE, M_FEE, 0, t_test_data.price))
+        )
+        .sc_call(
+            call_refund_offer(&t_test_data).expect(TxExpect::ok().no_result())
+        );
+}
+/*-------------------------------------------------------------------------*
+* Create a bundle offer (two different tokens in a single multi_esdt       *
+* transfer) and then take it. The taker receives both tokens at once.      *
+*-------------------------------------------------------------------------*/
+#[test]
+fn create_and_take_multi_esdt_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id_1 : &str = "PROPO-123456";
+    let t_str_token_id_2 : &str = "FUEL-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id_1, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id_1.as_bytes().to_vec()),BigUintValue::from(t_amount)).esdt_balance(BytesKey::from(t_str_token_id_2.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+
+    // Create two instances of TxESDT, one for each token of the bundle
+    let tx1 = TxESDT {
+        esdt_token_identifier: BytesValue::from(BytesKey::from(t_str_token_id_1.as_bytes().to_vec())),
+        nonce: U64Value::from(t_nonce),
+        esdt_value: BigUintValue::from(t_amount),
+    };
+
+    let tx2 = TxESDT {
+        esdt_token_identifier: BytesValue::from(BytesKey::from(t_str_token_id_2.as_bytes().to_vec())),
+        nonce: U64Value::from(t_nonce),
+        esdt_value: BigUintValue::from(t_amount),
+    };
+
+    // Create a vector and initialize it with tx1 and tx2
+    let tx_vec: Vec<TxESDT> = vec![tx1, tx2];
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            ScCallStep::new()
+            .from(AddressKey::from(&t_test_data.bidder_bech32)) //Address of the caller
+            .to("sc:exchangeplace") //destination of the call (the smart contract)
+            .multi_esdt_transfer(tx_vec) //Transfer the whole bundle
+            .function("createOffer") //The name of the function
+            .argument(BytesValue::from(t_test_data.price.to_be_bytes().as_ref())) //Price of the offer
+            .argument(BytesValue::from(t_test_data.taker_bech32.as_bytes())) //Address of the taker
+            .expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call(
+            call_take_offer(&t_test_data,M_FEE).expect(expect_settlement(t_test_data.price + M_FEE, M_FEE, 0, t_test_data.price))
+        )
+        .check_state_step( //The taker owns both tokens of the bundle, the bidder got paid
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().esdt_balance(BytesKey::from(t_str_token_id_1.as_bytes().to_vec()),BigUintValue::from(t_amount)).esdt_balance(BytesKey::from(t_str_token_id_2.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Create an offer priced in an ESDT and then take it paying with that ESDT.*
+*-------------------------------------------------------------------------*/
+#[test]
+fn create_and_take_esdt_priced_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_str_payment_token_id : &str = "USDC-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_payment_token_id.as_bytes().to_vec()),BigUintValue::from(t_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer_with_payment_token(&t_test_data, t_str_payment_token_id, 0).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call(
+            call_take_offer_with_esdt(&t_test_data, t_str_payment_token_id, 0, M_FEE).expect(expect_settlement(t_test_data.price + M_FEE, M_FEE, 0, t_test_data.price))
+        )
+        .check_state_step( //The bidder got paid in the payment token
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().esdt_balance(BytesKey::from(t_str_payment_token_id.as_bytes().to_vec()),BigUintValue::from(t_price)))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Try to take an offer priced in an ESDT by paying EGLD.                   *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Incorrect payment provided.")]
+fn take_esdt_priced_offer_with_egld_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_str_payment_token_id : &str = "USDC-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer_with_payment_token(&t_test_data, t_str_payment_token_id, 0).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call(
+            call_take_offer(&t_test_data,M_FEE).expect(expect_settlement(t_test_data.price + M_FEE, M_FEE, 0, t_test_data.price))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Create a barter offer (NFT for NFT) and then take it sending the wanted  *
+* NFT.                                                                     *
+*-------------------------------------------------------------------------*/
+#[test]
+fn create_and_take_barter_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_str_wanted_token_id : &str = "WANTED-123456";
+    let t_nonce: u64 = 1;
+    let t_wanted_nonce: u64 = 2;
+    let t_sent_nonce: u64 = 2;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint price
+    let t_price: u64 = 0;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_wanted_token_id.as_bytes().to_vec()),t_sent_nonce,BigUintValue::from(t_amount),Some("")));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the barter offer
+            call_create_barter_offer(&t_test_data, t_str_wanted_token_id, t_wanted_nonce, t_amount).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: take the offer sending the NFT
+            ScCallStep::new()
+            .from(AddressKey::from(&t_test_data.taker_bech32)) //AddressValue of the caller
+            .to("sc:exchangeplace") //destination of the call (the smart contract)
+            .esdt_transfer(BytesKey::from(t_str_wanted_token_id.as_bytes().to_vec()),t_sent_nonce,BigUintValue::from(t_amount)) //Send the NFT
+            .function("takeOffer") //The name of the function
+            .argument(BytesValue::from(t_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
+            .expect(expect_settlement(0, 0, 0, 0)) //Barter offers have no price
+        )
+        .check_state_step( //Both NFTs have been swapped
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().esdt_nft_balance_and_attributes(BytesKey::from(t_str_wanted_token_id.as_bytes().to_vec()),t_wanted_nonce,BigUintValue::from(1u64),None::<&str>))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(1u64),None::<&str>))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Try to take a barter offer sending an NFT of the wanted collection, but  *
+* with a different nonce.                                                  *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Incorrect payment provided.")]
+fn take_barter_offer_wrong_token_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_str_wanted_token_id : &str = "WANTED-123456";
+    let t_nonce: u64 = 1;
+    let t_wanted_nonce: u64 = 2;
+    let t_sent_nonce: u64 = 3;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint price
+    let t_price: u64 = 0;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_wanted_token_id.as_bytes().to_vec()),t_sent_nonce,BigUintValue::from(t_amount),Some("")));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the barter offer
+            call_create_barter_offer(&t_test_data, t_str_wanted_token_id, t_wanted_nonce, t_amount).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: take the offer sending the NFT
+            ScCallStep::new()
+            .from(AddressKey::from(&t_test_data.taker_bech32)) //AddressValue of the caller
+            .to("sc:exchangeplace") //destination of the call (the smart contract)
+            .esdt_transfer(BytesKey::from(t_str_wanted_token_id.as_bytes().to_vec()),t_sent_nonce,BigUintValue::from(t_amount)) //Send the NFT
+            .function("takeOffer") //The name of the function
+            .argument(BytesValue::from(t_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
+            .expect(expect_settlement(0, 0, 0, 0)) //Barter offers have no price
+        );
+}
+/*-------------------------------------------------------------------------*
+* Create an open offer (no taker) and take it from any address.            *
+*-------------------------------------------------------------------------*/
+#[test]
+fn create_and_take_open_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_open_address : &str = "erd1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq6gq4hu"; //No taker: the offer is open
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_open_address);
+    let t_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the open offer
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call(
+            call_take_offer(&t_taker_data,M_FEE).expect(expect_settlement(t_taker_data.price + M_FEE, M_FEE, 0, t_taker_data.price))
+        )
+        .check_state_step( //The taker got the token
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_taker_data.taker_bech32), CheckAccount::new().esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Create an open offer (no taker) and refund it.                           *
+*-------------------------------------------------------------------------*/
+#[test]
+fn create_and_refund_open_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_open_address : &str = "erd1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq6gq4hu"; //No taker: the offer is open
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_open_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the open offer
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call(
+            call_refund_offer(&t_test_data).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The bidder got the token back
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* The bidder tries to take its own open offer.                             *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Take offer not found.")]
+fn take_own_open_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_open_address : &str = "erd1qqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqqq6gq4hu"; //No taker: the offer is open
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_open_address);
+    let t_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_bidder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the open offer
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call(
+            call_take_offer(&t_taker_data,M_FEE).expect(expect_settlement(t_taker_data.price + M_FEE, M_FEE, 0, t_taker_data.price))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Create an offer with an expiry timestamp and try to take it once it has  *
+* expired.                                                                 *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Offer has expired.")]
+fn take_expired_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Timestamps
+    let t_creation_timestamp: u64 = 500;
+    let t_expiry_timestamp: u64 = 1000;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)))
+    .block_timestamp(t_creation_timestamp);
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid, expiring in the future
+            call_create_offer_with_expiry(&t_test_data, t_expiry_timestamp).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .set_state_step( //Let the offer expire
+            SetStateStep::new().block_timestamp(t_expiry_timestamp)
+        )
+        .sc_call(
+            call_take_offer(&t_test_data,M_FEE).expect(expect_settlement(t_test_data.price + M_FEE, M_FEE, 0, t_test_data.price))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Create an offer with an expiry timestamp and, once it has expired, let   *
+* the taker send it back to the bidder.                                    *
+*-------------------------------------------------------------------------*/
+#[test]
+fn reclaim_expired_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Timestamps
+    let t_creation_timestamp: u64 = 500;
+    let t_expiry_timestamp: u64 = 1000;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)))
+    .block_timestamp(t_creation_timestamp);
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid, expiring in the future
+            call_create_offer_with_expiry(&t_test_data, t_expiry_timestamp).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .set_state_step( //Let the offer expire
+            SetStateStep::new().block_timestamp(t_expiry_timestamp)
+        )
+        .sc_call(
+            call_reclaim_expired_offer(&t_test_data, &t_test_data.taker_bech32).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The bidder got the token back
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Place an offer in a Dutch auction with an expiry timestamp and try to    *
+* take it once it has expired, even though the price is still declining.   *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Offer has expired.")]
+fn take_expired_dutch_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint prices: halfway through the decline, the price is 700000000000
+    let t_start_price: u64 = 1000000000000;
+    let t_floor_price: u64 = 400000000000;
+    let t_current_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Timestamps: the offer expires halfway through the decline
+    let t_decline_start: u64 = 1000;
+    let t_decline_end: u64 = 2000;
+    let t_expiry_timestamp: u64 = 1500;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_start_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_current_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_start_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the offer in a Dutch auction, expiring in the future
+            call_create_dutch_offer_with_expiry(&t_test_data, t_floor_price, t_decline_start, t_decline_end, t_expiry_timestamp).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .set_state_step( //Let the offer expire
+            SetStateStep::new().block_timestamp(t_expiry_timestamp)
+        )
+        .sc_call(
+            call_take_offer(&t_taker_data,M_FEE).expect(expect_settlement(t_taker_data.price + M_FEE, M_FEE, 0, t_taker_data.price))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Create a barter offer with an expiry timestamp and, once it has expired, *
+* let the taker send it back to the bidder.                                *
+*-------------------------------------------------------------------------*/
+#[test]
+fn reclaim_expired_barter_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+    let t_offer_id: u64 = 1;
+
+    //Token wanted in exchange
+    let t_str_wanted_token_id : &str = "USDC-123456";
+    let t_wanted_amount: u64 = 500;
+
+    //Timestamps
+    let t_creation_timestamp: u64 = 500;
+    let t_expiry_timestamp: u64 = 1000;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, 0, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0))
+    .block_timestamp(t_creation_timestamp);
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the barter offer, expiring in the future
+            call_create_barter_offer_with_expiry(&t_test_data, t_str_wanted_token_id, 0, t_wanted_amount, t_expiry_timestamp).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .set_state_step( //Let the offer expire
+            SetStateStep::new().block_timestamp(t_expiry_timestamp)
+        )
+        .sc_call(
+            call_reclaim_expired_offer(&t_test_data, &t_test_data.taker_bech32).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The bidder got the token back
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Create a unit price offer, take part of it, and refund the rest.         *
+*-------------------------------------------------------------------------*/
+#[test]
+fn partial_take_and_refund_unit_price_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 1000;
+    let t_quantity: u64 = 400;
+
+    //BigUint price (per unit)
+    let t_price: u64 = 7000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price * t_quantity + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_unit_price_offer(&t_test_data).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: buy part of the tokens
+            call_take_offer_quantity(&t_test_data, t_quantity, M_FEE).expect(expect_settlement(t_test_data.price * t_quantity + M_FEE, M_FEE, 0, t_test_data.price * t_quantity))
+        )
+        .sc_call( //Third call: refund what is left
+            call_refund_offer(&t_test_data).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The taker got the quantity bought, the bidder got paid for it and got the rest back
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_quantity)))
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price * t_quantity)).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount - t_quantity)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Try to buy more tokens than the ones listed in a unit price offer.       *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Invalid quantity provided.")]
+fn take_unit_price_offer_too_much_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 1000;
+    let t_quantity: u64 = 1001;
+
+    //BigUint price (per unit)
+    let t_price: u64 = 7000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price * t_quantity + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_unit_price_offer(&t_test_data).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: buy part of the tokens
+            call_take_offer_quantity(&t_test_data, t_quantity, M_FEE).expect(expect_settlement(t_test_data.price * t_quantity + M_FEE, M_FEE, 0, t_test_data.price * t_quantity))
+        );
+}
+/*-------------------------------------------------------------------------*
+* The taker proposes a lower price, the bidder accepts it, and the taker   *
+* takes the offer at that price.                                           *
+*-------------------------------------------------------------------------*/
+#[test]
+fn accept_counter_and_take_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint prices
+    let t_price: u64 = 700000000000;
+    let t_counter_price: u64 = 500000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_counter_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_counter_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: the taker proposes a lower price
+            call_propose_counter(&t_test_data, t_counter_price).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: the bidder answers
+            call_answer_counter(&t_test_data, "acceptCounter").expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Fourth call: the taker takes the offer at the counter price
+            call_take_offer(&t_counter_data,M_FEE).expect(expect_settlement(t_counter_data.price + M_FEE, M_FEE, 0, t_counter_data.price))
+        )
+        .check_state_step( //The bidder got the counter price
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_counter_price)))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance(BigUintValue::from(t_price - t_counter_price)).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* The taker proposes a lower price, the bidder rejects it, and the taker   *
+* tries to take the offer at that price anyway.                            *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Incorrect payment provided.")]
+fn reject_counter_and_take_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint prices
+    let t_price: u64 = 700000000000;
+    let t_counter_price: u64 = 500000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_counter_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_counter_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: the taker proposes a lower price
+            call_propose_counter(&t_test_data, t_counter_price).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: the bidder answers
+            call_answer_counter(&t_test_data, "rejectCounter").expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Fourth call: the taker takes the offer at the counter price
+            call_take_offer(&t_counter_data,M_FEE).expect(expect_settlement(t_counter_data.price + M_FEE, M_FEE, 0, t_counter_data.price))
+        );
+}
+/*-------------------------------------------------------------------------*
+* The bidder updates the price of an offer, and the taker takes it at the  *
+* new price.                                                               *
+*-------------------------------------------------------------------------*/
+#[test]
+fn update_price_and_take_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint prices
+    let t_price: u64 = 700000000000;
+    let t_new_price: u64 = 800000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_new_price_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_new_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_new_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: the bidder raises the price
+            call_update_offer_price(&t_test_data, t_new_price).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: take the offer at the new price
+            call_take_offer_expecting_price(&t_new_price_data,M_FEE).expect(expect_settlement(t_new_price_data.price + M_FEE, M_FEE, 0, t_new_price_data.price))
+        )
+        .check_state_step( //The bidder got the new price
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_new_price)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* The bidder updates the price of an offer while the taker is taking it at *
+* the old price.                                                           *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Offer price has changed.")]
+fn update_price_racing_take_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint prices
+    let t_price: u64 = 700000000000;
+    let t_new_price: u64 = 800000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_new_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: the bidder raises the price
+            call_update_offer_price(&t_test_data, t_new_price).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: take the offer expecting the old price
+            call_take_offer_expecting_price(&t_test_data,M_FEE).expect(expect_settlement(t_test_data.price + M_FEE, M_FEE, 0, t_test_data.price))
+        );
+}
+/*-------------------------------------------------------------------------*
+* The bidder moves an offer to a new taker, who takes it. The old taker    *
+* cannot take it anymore.                                                  *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Take offer not found.")]
+fn change_taker_and_take_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_new_taker_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_new_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_new_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)))
+    .put_account(AddressKey::from(&bech32::decode(t_new_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: move the offer to the new taker
+            call_change_offer_taker(&t_test_data, &t_new_taker_data.taker_bech32).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: the new taker takes the offer
+            call_take_offer(&t_new_taker_data,M_FEE).expect(expect_settlement(t_new_taker_data.price + M_FEE, M_FEE, 0, t_new_taker_data.price))
+        )
+        .check_state_step( //The new taker got the token
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_new_taker_data.taker_bech32), CheckAccount::new().esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        )
+        .sc_call( //Fourth call: the old taker tries to take the offer
+            call_take_offer(&t_test_data,M_FEE).expect(expect_settlement(t_test_data.price + M_FEE, M_FEE, 0, t_test_data.price))
+        );
+}
+/*-------------------------------------------------------------------------*
+* The bidder tries to move an offer to a smart contract address. Normally  *
+* the contract is deployed as Non-payable by smart contract, so this is for*
+* pure testing.                                                            *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Taker address is from a smart contract.")]
+fn change_taker_sc_address_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_new_taker_address : &str = "erd1qqqqqqqqqqqqqpgq5cfxcvq5dqp290j2q9gw5yc8fcremmlqplkqtly3rs";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_new_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_new_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)))
+    .put_account(AddressKey::from(&bech32::decode(t_new_taker_address)), Account::new().nonce(0).code(EXCHANGE_PLACE_PATH_EXPR).balance(BigUintValue::from(t_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: move the offer to the new taker
+            call_change_offer_taker(&t_test_data, &t_new_taker_data.taker_bech32).expect(TxExpect::ok().no_result())
+        )
+;
+}
+/*-------------------------------------------------------------------------*
+* Top up an offer, withdraw part of it, and take it. The taker gets what   *
+* is left in escrow.                                                       *
+*-------------------------------------------------------------------------*/
+#[test]
+fn add_withdraw_and_take_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amounts
+    let t_amount: u64 = 1000;
+    let t_added_amount: u64 = 500;
+    let t_withdrawn_amount: u64 = 300;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount + t_added_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: top up the offer
+            call_add_to_offer(&t_test_data, t_added_amount).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: withdraw part of the offer
+            call_withdraw_from_offer(&t_test_data, t_withdrawn_amount).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Fourth call: take the offer
+            call_take_offer(&t_test_data,M_FEE).expect(expect_settlement(t_test_data.price + M_FEE, M_FEE, 0, t_test_data.price))
+        )
+        .check_state_step( //The taker got what was left in escrow, the bidder what was withdrawn
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount + t_added_amount - t_withdrawn_amount)))
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price)).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_withdrawn_amount)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Try to withdraw everything that is in escrow from an offer.              *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Invalid amount provided.")]
+fn withdraw_whole_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amounts
+    let t_amount: u64 = 1000;
+    let t_added_amount: u64 = 500;
+    let t_withdrawn_amount: u64 = 1500;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount + t_added_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the bid
+            call_create_offer(&t_test_data,M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: top up the offer
+            call_add_to_offer(&t_test_data, t_added_amount).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: withdraw part of the offer
+            call_withdraw_from_offer(&t_test_data, t_withdrawn_amount).expect(TxExpect::ok().no_result())
+        );
+}
+/*-------------------------------------------------------------------------*
+* A collector locks EGLD to buy an NFT, and the holder accepts the bid.    *
+*-------------------------------------------------------------------------*/
+#[test]
+fn create_and_accept_bid_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_bid_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_holder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_bid_id, t_bidder_address, t_holder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price)))
+    .put_account(AddressKey::from(&bech32::decode(t_holder_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))); //Into bytes must be used in order for the VM to correctly parse the token ID string
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: lock the price
+            call_create_bid(&t_test_data).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: the holder sends the NFT
+            call_accept_bid(&t_test_data, t_nonce).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The NFT and the price have been swapped
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance("0").esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance(BigUintValue::from(t_price)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* A collector locks EGLD to buy an NFT, and cancels the bid.               *
+*-------------------------------------------------------------------------*/
+#[test]
+fn create_and_cancel_bid_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_bid_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_holder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_bid_id, t_bidder_address, t_holder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price)))
+    .put_account(AddressKey::from(&bech32::decode(t_holder_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))); //Into bytes must be used in order for the VM to correctly parse the token ID string
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: lock the price
+            call_create_bid(&t_test_data).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: cancel the bid
+            call_cancel_bid(&t_test_data).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The bidder got the price back
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* The holder tries to fill a bid with another nonce of the same token.     *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Incorrect token provided.")]
+fn accept_bid_wrong_nonce_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint price
+    let t_price: u64 = 700000000000;
+    let t_bid_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_holder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_bid_id, t_bidder_address, t_holder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price)))
+    .put_account(AddressKey::from(&bech32::decode(t_holder_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce + 1,BigUintValue::from(t_amount),Some(""))); //Into bytes must be used in order for the VM to correctly parse the token ID string
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: lock the price
+            call_create_bid(&t_test_data).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: the holder sends the wrong NFT
+            call_accept_bid(&t_test_data, t_nonce + 1)
+        );
+}
+/*-------------------------------------------------------------------------*
+* A collector bids for three items of a collection. The holder fills two   *
+* slots with different nonces, and the collector cancels the bid to get    *
+* the price of the last slot back.                                         *
+*-------------------------------------------------------------------------*/
+#[test]
+fn fill_and_cancel_collection_bid_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce_1: u64 = 5;
+    let t_nonce_2: u64 = 9;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint price (of the three items)
+    let t_slots: u64 = 3;
+    let t_price: u64 = 300000000000;
+    let t_bid_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_holder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, 0, t_amount, t_price, t_bid_id, t_bidder_address, t_holder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price)))
+    .put_account(AddressKey::from(&bech32::decode(t_holder_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce_1,BigUintValue::from(t_amount),Some("")).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce_2,BigUintValue::from(t_amount),Some(""))); //Into bytes must be used in order for the VM to correctly parse the token ID string
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: lock the price of the three items
+            call_create_collection_bid(&t_test_data, t_slots).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: the holder sends a first NFT
+            call_accept_bid(&t_test_data, t_nonce_1).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: the holder sends a second NFT, with another nonce
+            call_accept_bid(&t_test_data, t_nonce_2).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Fourth call: cancel the bid
+            call_cancel_bid(&t_test_data).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The holder got paid two slots, the bidder got the NFTs and the last slot back
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price / t_slots)).esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce_1,BigUintValue::from(t_amount),Some("")).esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce_2,BigUintValue::from(t_amount),Some("")))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance(BigUintValue::from(t_price / t_slots * 2)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* A collector bids for items of a collection with a payment that cannot be *
+* split equally between them.                                              *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Payment must be divisible by the number of items.")]
+fn create_collection_bid_indivisible_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce_1: u64 = 5;
+    let t_nonce_2: u64 = 9;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint price (of the three items)
+    let t_slots: u64 = 3;
+    let t_price: u64 = 300000000000;
+    let t_bid_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_holder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, 0, t_amount, t_price, t_bid_id, t_bidder_address, t_holder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price)))
+    .put_account(AddressKey::from(&bech32::decode(t_holder_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce_1,BigUintValue::from(t_amount),Some("")).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce_2,BigUintValue::from(t_amount),Some(""))); //Into bytes must be used in order for the VM to correctly parse the token ID string
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: lock the price for seven items
+            call_create_collection_bid(&t_test_data, t_slots + 4)
+         );
+}
+/*-------------------------------------------------------------------------*
+* Place an offer in a Dutch auction, and take it halfway through the       *
+* price decline, at the current price.                                     *
+*-------------------------------------------------------------------------*/
+#[test]
+fn take_dutch_offer_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint prices: halfway through the decline, the price is 700000000000
+    let t_start_price: u64 = 1000000000000;
+    let t_floor_price: u64 = 400000000000;
+    let t_current_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Timestamps
+    let t_decline_start: u64 = 1000;
+    let t_decline_end: u64 = 2000;
+    let t_take_timestamp: u64 = 1500;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_start_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_current_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_start_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the offer in a Dutch auction
+            call_create_dutch_offer(&t_test_data, t_floor_price, t_decline_start, t_decline_end).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .set_state_step( //Halfway through the price decline
+            SetStateStep::new().block_timestamp(t_take_timestamp)
+        )
+        .sc_query( //The view reports the current price
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getCurrentPrice")
+            .argument(&t_offer_id.to_string()) //ID of the offer
+            .expect(TxExpect::ok().result(&t_current_price.to_string()))
+        )
+        .sc_call( //Second call: take the offer at the current price
+            call_take_offer(&t_taker_data,M_FEE).expect(expect_settlement(t_taker_data.price + M_FEE, M_FEE, 0, t_taker_data.price))
+        )
+        .check_state_step( //The bidder got the current price, the taker kept the rest
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_current_price)))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance(BigUintValue::from(t_start_price - t_current_price)).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Place an offer in a Dutch auction, and take it halfway through the       *
+* price decline, expecting to pay the current price rather than the start  *
+* price.                                                                   *
+*-------------------------------------------------------------------------*/
+#[test]
+fn take_dutch_offer_expecting_price_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint prices: halfway through the decline, the price is 700000000000
+    let t_start_price: u64 = 1000000000000;
+    let t_floor_price: u64 = 400000000000;
+    let t_current_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Timestamps
+    let t_decline_start: u64 = 1000;
+    let t_decline_end: u64 = 2000;
+    let t_take_timestamp: u64 = 1500;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_start_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_current_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_start_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the offer in a Dutch auction
+            call_create_dutch_offer(&t_test_data, t_floor_price, t_decline_start, t_decline_end).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .set_state_step( //Halfway through the price decline
+            SetStateStep::new().block_timestamp(t_take_timestamp)
+        )
+        .sc_call( //Second call: take the offer, expecting the current price
+            call_take_offer_expecting_price(&t_taker_data,M_FEE).expect(expect_settlement(t_taker_data.price + M_FEE, M_FEE, 0, t_taker_data.price))
+        )
+        .check_state_step( //The bidder got the current price, the taker kept the rest
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_current_price)))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance(BigUintValue::from(t_start_price - t_current_price)).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Place an offer in a Dutch auction, and take it halfway through the       *
+* price decline paying the start price. The offer is settled at the        *
+* current price and the difference is refunded to the taker.               *
+*-------------------------------------------------------------------------*/
+#[test]
+fn take_dutch_offer_overpaid_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint prices: halfway through the decline, the price is 700000000000
+    let t_start_price: u64 = 1000000000000;
+    let t_floor_price: u64 = 400000000000;
+    let t_current_price: u64 = 700000000000;
+    let t_offer_id: u64 = 1;
+
+    //Timestamps
+    let t_decline_start: u64 = 1000;
+    let t_decline_end: u64 = 2000;
+    let t_take_timestamp: u64 = 1500;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_start_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_start_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_start_price + M_FEE)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: place the offer in a Dutch auction
+            call_create_dutch_offer(&t_test_data, t_floor_price, t_decline_start, t_decline_end).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .set_state_step( //Halfway through the price decline
+            SetStateStep::new().block_timestamp(t_take_timestamp)
+        )
+        .sc_call( //Second call: take the offer paying the start price
+            call_take_offer(&t_taker_data,M_FEE).expect(expect_settlement(t_current_price + M_FEE, M_FEE, 0, t_current_price))
+        )
+        .check_state_step( //The bidder got the current price, the taker was refunded the rest
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_current_price)))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance(BigUintValue::from(t_start_price - t_current_price)).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Put an NFT up for auction. A second bidder outbids the first one, who    *
+* is refunded, and wins the auction once it is settled.                    *
+*-------------------------------------------------------------------------*/
+#[test]
+fn english_auction_outbid_and_settle_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint prices
+    let t_reserve_price: u64 = 500000000000;
+    let t_min_increment: u64 = 100000000000;
+    let t_balance: u64 = 1000000000000;
+    let t_auction_id: u64 = 1;
+
+    //Timestamps
+    let t_creation_timestamp: u64 = 500;
+    let t_end_timestamp: u64 = 1000;
+
+    //Addresses
+    let t_seller_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_first_bidder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_second_bidder_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_reserve_price, t_auction_id, t_seller_address, t_first_bidder_address);
+    let t_second_bidder: Address = bech32::decode(t_second_bidder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_seller_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_first_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_balance)))
+    .put_account(AddressKey::from(&bech32::decode(t_second_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_balance)))
+    .block_timestamp(t_creation_timestamp);
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: put the NFT up for auction
+            call_create_auction(&t_test_data, t_min_increment, t_end_timestamp).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: first bid, at the reserve price
+            call_bid_on_auction(&t_test_data, &t_test_data.taker_bech32, t_reserve_price).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: second bid, outbidding the first one
+            call_bid_on_auction(&t_test_data, &t_second_bidder, t_reserve_price + t_min_increment).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The first bidder has been refunded
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance(BigUintValue::from(t_balance)))
+        )
+        .set_state_step( //Let the auction end
+            SetStateStep::new().block_timestamp(t_end_timestamp)
+        )
+        .sc_call( //Fourth call: settle the auction
+            call_settle_auction(&t_test_data, &t_test_data.taker_bech32).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The winner got the NFT and the seller the highest bid
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_second_bidder), CheckAccount::new().balance(BigUintValue::from(t_balance - t_reserve_price - t_min_increment)).esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_reserve_price + t_min_increment - M_FEE)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Put an NFT up for auction, and try to outbid the first bidder by less    *
+* than the minimum increment.                                              *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Bid too low.")]
+fn english_auction_bid_too_low_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint prices
+    let t_reserve_price: u64 = 500000000000;
+    let t_min_increment: u64 = 100000000000;
+    let t_balance: u64 = 1000000000000;
+    let t_auction_id: u64 = 1;
+
+    //Timestamps
+    let t_creation_timestamp: u64 = 500;
+    let t_end_timestamp: u64 = 1000;
+
+    //Addresses
+    let t_seller_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_first_bidder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_second_bidder_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_reserve_price, t_auction_id, t_seller_address, t_first_bidder_address);
+    let t_second_bidder: Address = bech32::decode(t_second_bidder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_seller_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_first_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_balance)))
+    .put_account(AddressKey::from(&bech32::decode(t_second_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_balance)))
+    .block_timestamp(t_creation_timestamp);
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: put the NFT up for auction
+            call_create_auction(&t_test_data, t_min_increment, t_end_timestamp).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: first bid, at the reserve price
+            call_bid_on_auction(&t_test_data, &t_test_data.taker_bech32, t_reserve_price).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: second bid, not high enough
+            call_bid_on_auction(&t_test_data, &t_second_bidder, t_reserve_price + t_min_increment - 1)
+        );
+}
+/*-------------------------------------------------------------------------*
+* Put an NFT up for a sealed-bid auction. Both bidders reveal their bid,   *
+* and the highest one wins once the auction is settled, getting back what  *
+* is left of its deposit. The other bidder then withdraws its deposit.     *
+*-------------------------------------------------------------------------*/
+#[test]
+fn sealed_auction_reveal_and_settle_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint prices
+    let t_reserve_price: u64 = 500000000000;
+    let t_first_bid: u64 = 600000000000;
+    let t_first_deposit: u64 = 800000000000;
+    let t_second_bid: u64 = 700000000000;
+    let t_balance: u64 = 1000000000000;
+    let t_auction_id: u64 = 1;
+
+    //Timestamps
+    let t_creation_timestamp: u64 = 500;
+    let t_commit_end: u64 = 1000;
+    let t_reveal_end: u64 = 2000;
+
+    //Addresses
+    let t_seller_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_first_bidder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_second_bidder_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_reserve_price, t_auction_id, t_seller_address, t_first_bidder_address);
+    let t_second_bidder: Address = bech32::decode(t_second_bidder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_seller_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_first_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_balance)))
+    .put_account(AddressKey::from(&bech32::decode(t_second_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_balance)))
+    .block_timestamp(t_creation_timestamp);
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: put the NFT up for a sealed-bid auction
+            call_create_sealed_auction(&t_test_data, t_commit_end, t_reveal_end).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: first bidder commits, hiding the bid in a higher deposit
+            call_commit_sealed_bid(&t_test_data, &t_test_data.taker_bech32, t_first_bid, "first salt", t_first_deposit).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: second bidder commits
+            call_commit_sealed_bid(&t_test_data, &t_second_bidder, t_second_bid, "second salt", t_second_bid).expect(TxExpect::ok().no_result())
+        )
+        .set_state_step( //Start the reveal phase
+            SetStateStep::new().block_timestamp(t_commit_end)
+        )
+        .sc_call( //Fourth call: first bidder reveals
+            call_reveal_sealed_bid(&t_test_data, &t_test_data.taker_bech32, t_first_bid, "first salt").expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Fifth call: second bidder reveals a higher bid
+            call_reveal_sealed_bid(&t_test_data, &t_second_bidder, t_second_bid, "second salt").expect(TxExpect::ok().no_result())
+        )
+        .set_state_step( //End the reveal phase
+            SetStateStep::new().block_timestamp(t_reveal_end)
+        )
+        .sc_call( //Sixth call: settle the auction
+            call_settle_sealed_auction(&t_test_data, &t_test_data.taker_bech32).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Seventh call: the other bidder withdraws its deposit
+            call_withdraw_sealed_deposit(&t_test_data, &t_test_data.taker_bech32).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The winner got the NFT, the seller the winning bid, and the other bidder the whole deposit
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_second_bidder), CheckAccount::new().balance(BigUintValue::from(t_balance - t_second_bid)).esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance(BigUintValue::from(t_balance)))
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_second_bid - M_FEE)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Put an NFT up for a sealed-bid auction, and try to reveal a bid with     *
+* another salt than the one committed.                                     *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Bid does not match its commitment.")]
+fn sealed_auction_reveal_wrong_salt_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint prices
+    let t_reserve_price: u64 = 500000000000;
+    let t_first_bid: u64 = 600000000000;
+    let t_first_deposit: u64 = 800000000000;
+    let t_second_bid: u64 = 700000000000;
+    let t_balance: u64 = 1000000000000;
+    let t_auction_id: u64 = 1;
+
+    //Timestamps
+    let t_creation_timestamp: u64 = 500;
+    let t_commit_end: u64 = 1000;
+    let t_reveal_end: u64 = 2000;
+
+    //Addresses
+    let t_seller_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_first_bidder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_second_bidder_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_reserve_price, t_auction_id, t_seller_address, t_first_bidder_address);
+    let t_second_bidder: Address = bech32::decode(t_second_bidder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_seller_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_first_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_balance)))
+    .put_account(AddressKey::from(&bech32::decode(t_second_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_balance)))
+    .block_timestamp(t_creation_timestamp);
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: put the NFT up for a sealed-bid auction
+            call_create_sealed_auction(&t_test_data, t_commit_end, t_reveal_end).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: first bidder commits, hiding the bid in a higher deposit
+            call_commit_sealed_bid(&t_test_data, &t_test_data.taker_bech32, t_first_bid, "first salt", t_first_deposit).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: second bidder commits
+            call_commit_sealed_bid(&t_test_data, &t_second_bidder, t_second_bid, "second salt", t_second_bid).expect(TxExpect::ok().no_result())
+        )
+        .set_state_step( //Start the reveal phase
+            SetStateStep::new().block_timestamp(t_commit_end)
+        )
+        .sc_call( //Fourth call: first bidder reveals with the wrong salt
+            call_reveal_sealed_bid(&t_test_data, &t_test_data.taker_bech32, t_first_bid, "second salt")
+        );
+}
+/*-------------------------------------------------------------------------*
+* Put an NFT up for a sealed-bid auction, and try to commit a bid with a   *
+* deposit below the reserve price.                                         *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Deposit below the reserve price.")]
+fn sealed_auction_deposit_below_reserve_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint prices
+    let t_reserve_price: u64 = 500000000000;
+    let t_deposit: u64 = 1;
+    let t_balance: u64 = 1000000000000;
+    let t_auction_id: u64 = 1;
+
+    //Timestamps
+    let t_creation_timestamp: u64 = 500;
+    let t_commit_end: u64 = 1000;
+    let t_reveal_end: u64 = 2000;
+
+    //Addresses
+    let t_seller_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_bidder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_reserve_price, t_auction_id, t_seller_address, t_bidder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_seller_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_balance)))
+    .block_timestamp(t_creation_timestamp);
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: put the NFT up for a sealed-bid auction
+            call_create_sealed_auction(&t_test_data, t_commit_end, t_reveal_end).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: the bidder commits with a dust deposit
+            call_commit_sealed_bid(&t_test_data, &t_test_data.taker_bech32, t_deposit, "salt", t_deposit)
+        );
+}
+/*-------------------------------------------------------------------------*
+* Put an NFT up for a sealed-bid auction, and try to withdraw the deposit  *
+* of the winning bid instead of settling the auction.                      *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Winning bid is refunded when the auction is settled.")]
+fn sealed_auction_withdraw_winning_deposit_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint prices
+    let t_reserve_price: u64 = 500000000000;
+    let t_bid: u64 = 600000000000;
+    let t_balance: u64 = 1000000000000;
+    let t_auction_id: u64 = 1;
+
+    //Timestamps
+    let t_creation_timestamp: u64 = 500;
+    let t_commit_end: u64 = 1000;
+    let t_reveal_end: u64 = 2000;
+
+    //Addresses
+    let t_seller_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_bidder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_reserve_price, t_auction_id, t_seller_address, t_bidder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_seller_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_balance)))
+    .block_timestamp(t_creation_timestamp);
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: put the NFT up for a sealed-bid auction
+            call_create_sealed_auction(&t_test_data, t_commit_end, t_reveal_end).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: the bidder commits
+            call_commit_sealed_bid(&t_test_data, &t_test_data.taker_bech32, t_bid, "salt", t_bid).expect(TxExpect::ok().no_result())
+        )
+        .set_state_step( //Start the reveal phase
+            SetStateStep::new().block_timestamp(t_commit_end)
+        )
+        .sc_call( //Third call: the bidder reveals the winning bid
+            call_reveal_sealed_bid(&t_test_data, &t_test_data.taker_bech32, t_bid, "salt").expect(TxExpect::ok().no_result())
+        )
+        .set_state_step( //End the reveal phase
+            SetStateStep::new().block_timestamp(t_reveal_end)
+        )
+        .sc_call( //Fourth call: the winner tries to withdraw its deposit
+            call_withdraw_sealed_deposit(&t_test_data, &t_test_data.taker_bech32)
+        );
+}
+/*-------------------------------------------------------------------------*
+* Put an NFT up for a sealed-bid auction, and try to commit the hash       *
+* committed by another bidder, copied from the chain.                      *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Hash already committed.")]
+fn sealed_auction_copied_hash_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint prices
+    let t_reserve_price: u64 = 500000000000;
+    let t_first_bid: u64 = 600000000000;
+    let t_first_deposit: u64 = 800000000000;
+    let t_balance: u64 = 1000000000000;
+    let t_auction_id: u64 = 1;
+
+    //Timestamps
+    let t_creation_timestamp: u64 = 500;
+    let t_commit_end: u64 = 1000;
+    let t_reveal_end: u64 = 2000;
+
+    //Addresses
+    let t_seller_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_first_bidder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_second_bidder_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_reserve_price, t_auction_id, t_seller_address, t_first_bidder_address);
+    let t_second_bidder: Address = bech32::decode(t_second_bidder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_seller_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_first_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_balance)))
+    .put_account(AddressKey::from(&bech32::decode(t_second_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_balance)))
+    .block_timestamp(t_creation_timestamp);
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: put the NFT up for a sealed-bid auction
+            call_create_sealed_auction(&t_test_data, t_commit_end, t_reveal_end).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: first bidder commits
+            call_commit_sealed_bid(&t_test_data, &t_test_data.taker_bech32, t_first_bid, "first salt", t_first_deposit).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: second bidder commits the hash of the first bidder
+            ScCallStep::new()
+            .from(AddressKey::from(&t_second_bidder)) //AddressValue of the caller
+            .to("sc:exchangeplace") //Destination of the call (the smart contract)
+            .egld_value(BigUintValue::from(t_first_deposit)) //Escrow the deposit
+            .function("commitSealedBid") //The name of the function
+            .argument(BytesValue::from(t_test_data.offer_id.to_be_bytes().as_ref())) //ID of the auction
+            .argument(BytesValue::from(sealed_bid_hash(&t_test_data.taker_bech32, t_first_bid, "first salt"))) //Hash copied from the first bidder
+        );
+}
+/*-------------------------------------------------------------------------*
+* Three participants agree on a barter ring. Once the last one deposits    *
+* its NFT, every NFT goes to the next participant of the ring.             *
+*-------------------------------------------------------------------------*/
+#[test]
+fn fund_and_execute_ring_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    //One NFT for each participant
+    let t_str_token_id_a : &str = "PROPO-123456";
+    let t_str_token_id_b : &str = "PROPO-179101";
+    let t_str_token_id_c : &str = "PROPO-111111";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+    let t_ring_id: u64 = 1;
+
+    //Addresses
+    let t_address_a: Address = bech32::decode("erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0");
+    let t_address_b: Address = bech32::decode("erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq");
+    let t_address_c: Address = bech32::decode("erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan");
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&t_address_a), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id_a.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&t_address_b), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id_b.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+    .put_account(AddressKey::from(&t_address_c), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id_c.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: A creates the ring A -> B -> C -> A
+            call_create_ring(&t_address_a, &[(&t_address_a, t_str_token_id_a, t_nonce, t_amount), (&t_address_b, t_str_token_id_b, t_nonce, t_amount), (&t_address_c, t_str_token_id_c, t_nonce, t_amount)]).expect(expect_new_id(t_ring_id))
+         )
+        .sc_call( //Second call: A deposits its NFT
+            call_fund_ring(&t_address_a, t_ring_id, t_str_token_id_a, t_nonce, t_amount).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: B deposits its NFT
+            call_fund_ring(&t_address_b, t_ring_id, t_str_token_id_b, t_nonce, t_amount).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Fourth call: C deposits its NFT, which completes the ring
+            call_fund_ring(&t_address_c, t_ring_id, t_str_token_id_c, t_nonce, t_amount).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //A's NFT went to B, B's to C, and C's to A
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_address_a), CheckAccount::new().esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id_c.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+            .put_account(AddressKey::from(&t_address_b), CheckAccount::new().esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id_a.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+            .put_account(AddressKey::from(&t_address_c), CheckAccount::new().esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id_b.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Three participants agree on a barter ring. Before the last one deposits  *
+* its NFT, it cancels the ring, and the NFTs deposited go back to A and B. *
+*-------------------------------------------------------------------------*/
+#[test]
+fn fund_and_cancel_ring_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    //One NFT for each participant
+    let t_str_token_id_a : &str = "PROPO-123456";
+    let t_str_token_id_b : &str = "PROPO-179101";
+    let t_str_token_id_c : &str = "PROPO-111111";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+    let t_ring_id: u64 = 1;
+
+    //Addresses
+    let t_address_a: Address = bech32::decode("erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0");
+    let t_address_b: Address = bech32::decode("erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq");
+    let t_address_c: Address = bech32::decode("erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan");
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&t_address_a), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id_a.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&t_address_b), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id_b.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+    .put_account(AddressKey::from(&t_address_c), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id_c.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: A creates the ring A -> B -> C -> A
+            call_create_ring(&t_address_a, &[(&t_address_a, t_str_token_id_a, t_nonce, t_amount), (&t_address_b, t_str_token_id_b, t_nonce, t_amount), (&t_address_c, t_str_token_id_c, t_nonce, t_amount)]).expect(expect_new_id(t_ring_id))
+         )
+        .sc_call( //Second call: A deposits its NFT
+            call_fund_ring(&t_address_a, t_ring_id, t_str_token_id_a, t_nonce, t_amount).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: B deposits its NFT
+            call_fund_ring(&t_address_b, t_ring_id, t_str_token_id_b, t_nonce, t_amount).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Fourth call: C cancels the ring
+            call_cancel_ring(&t_address_c, t_ring_id).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //A and B got their NFT back
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_address_a), CheckAccount::new().esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id_a.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+            .put_account(AddressKey::from(&t_address_b), CheckAccount::new().esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id_b.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Three participants agree on a barter ring. B tries to deposit another    *
+* NFT than the one it committed to.                                        *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Incorrect token provided.")]
+fn fund_ring_wrong_token_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    //One NFT for each participant
+    let t_str_token_id_a : &str = "PROPO-123456";
+    let t_str_token_id_b : &str = "PROPO-179101";
+    let t_str_token_id_c : &str = "PROPO-111111";
+    let t_nonce: u64 = 1;
+    let t_other_nonce: u64 = 2;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+    let t_ring_id: u64 = 1;
+
+    //Addresses
+    let t_address_a: Address = bech32::decode("erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0");
+    let t_address_b: Address = bech32::decode("erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq");
+    let t_address_c: Address = bech32::decode("erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan");
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&t_address_a), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id_a.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&t_address_b), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id_b.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")).esdt_nft_balance(BytesKey::from(t_str_token_id_b.as_bytes().to_vec()),t_other_nonce,BigUintValue::from(t_amount),Some("")))
+    .put_account(AddressKey::from(&t_address_c), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id_c.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: A creates the ring A -> B -> C -> A
+            call_create_ring(&t_address_a, &[(&t_address_a, t_str_token_id_a, t_nonce, t_amount), (&t_address_b, t_str_token_id_b, t_nonce, t_amount), (&t_address_c, t_str_token_id_c, t_nonce, t_amount)]).expect(expect_new_id(t_ring_id))
+         )
+        .sc_call( //Second call: A deposits its NFT
+            call_fund_ring(&t_address_a, t_ring_id, t_str_token_id_a, t_nonce, t_amount).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: B deposits another NFT of the same collection
+            call_fund_ring(&t_address_b, t_ring_id, t_str_token_id_b, t_other_nonce, t_amount)
+        );
+}
+/*-------------------------------------------------------------------------*
+* Try to create a ring between only two participants.                      *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "A ring needs at least three participants.")]
+fn create_ring_two_participants_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    //One NFT for each participant
+    let t_str_token_id_a : &str = "PROPO-123456";
+    let t_str_token_id_b : &str = "PROPO-179101";
+    let t_str_token_id_c : &str = "PROPO-111111";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //Addresses
+    let t_address_a: Address = bech32::decode("erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0");
+    let t_address_b: Address = bech32::decode("erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq");
+    let t_address_c: Address = bech32::decode("erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan");
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&t_address_a), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id_a.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&t_address_b), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id_b.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+    .put_account(AddressKey::from(&t_address_c), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id_c.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: A creates a ring with B only
+            call_create_ring(&t_address_a, &[(&t_address_a, t_str_token_id_a, t_nonce, t_amount), (&t_address_b, t_str_token_id_b, t_nonce, t_amount)])
+         );
+}
+/*-------------------------------------------------------------------------*
+* A collector bids for two items of a collection tagged exactly as rare.   *
+* The holder fills a slot with an NFT tagged rare, but an NFT tagged       *
+* rarest does not match.                                                   *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Token attributes do not match the bid.")]
+fn fill_attribute_entry_bid_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_rare_nonce: u64 = 5;
+    let t_rarest_nonce: u64 = 9;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint price (of the two items)
+    let t_slots: u64 = 2;
+    let t_price: u64 = 200000000000;
+    let t_bid_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_holder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, 0, t_amount, t_price, t_bid_id, t_bidder_address, t_holder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price)))
+    .put_account(AddressKey::from(&bech32::decode(t_holder_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_rare_nonce,BigUintValue::from(t_amount),Some("str:metadata:cid;tags:rare")).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_rarest_nonce,BigUintValue::from(t_amount),Some("str:metadata:cid;tags:rarest"))); //Into bytes must be used in order for the VM to correctly parse the token ID string
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: lock the price of two items matching the predicate
+            call_create_attribute_bid(&t_test_data, t_slots, 2, "tags:rare").expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: the holder sends the NFT tagged rare
+            call_accept_bid(&t_test_data, t_rare_nonce).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: the holder sends the NFT tagged rarest
+            call_accept_bid(&t_test_data, t_rarest_nonce)
+        );
+}
+/*-------------------------------------------------------------------------*
+* A collector bids for two items of a collection whose attributes contain  *
+* rare. The holder fills both slots.                                       *
+*-------------------------------------------------------------------------*/
+#[test]
+fn fill_attribute_contains_bid_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_rare_nonce: u64 = 5;
+    let t_rarest_nonce: u64 = 9;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint price (of the two items)
+    let t_slots: u64 = 2;
+    let t_price: u64 = 200000000000;
+    let t_bid_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_holder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, 0, t_amount, t_price, t_bid_id, t_bidder_address, t_holder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price)))
+    .put_account(AddressKey::from(&bech32::decode(t_holder_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_rare_nonce,BigUintValue::from(t_amount),Some("str:metadata:cid;tags:rare")).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_rarest_nonce,BigUintValue::from(t_amount),Some("str:metadata:cid;tags:rarest"))); //Into bytes must be used in order for the VM to correctly parse the token ID string
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: lock the price of two items matching the predicate
+            call_create_attribute_bid(&t_test_data, t_slots, 1, "rare").expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: the holder sends the NFT tagged rare
+            call_accept_bid(&t_test_data, t_rare_nonce).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: the holder sends the NFT tagged rarest
+            call_accept_bid(&t_test_data, t_rarest_nonce).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The holder got paid both slots, the bidder got both NFTs
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance("0").esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_rare_nonce,BigUintValue::from(t_amount),Some("metadata:cid;tags:rare")).esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_rarest_nonce,BigUintValue::from(t_amount),Some("metadata:cid;tags:rarest")))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance(BigUintValue::from(t_price)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Create an offer for an NFT with 10% royalties, check the royalties       *
+* reported by the view, and take the offer: the creator receives the       *
+* royalties and the bidder receives the rest of the price.                 *
+*-------------------------------------------------------------------------*/
+#[test]
+fn take_offer_royalties_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint price, and the royalties of the creator (1000 hundredths of a percent)
+    let t_price: u64 = 200000000000;
+    let t_royalties: u64 = 1000;
+    let t_royalties_amount: u64 = 20000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_creator_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_nft_all_properties(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""),t_royalties,Some(format!("bech32:{}", t_creator_address).as_str()),None,Vec::new())) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)))
+    .put_account(AddressKey::from(&bech32::decode(t_creator_address)), Account::new().nonce(0));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: create the offer
+            call_create_offer(&t_test_data, M_FEE).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_query( //The view reports the royalties of the creator
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getOfferRoyalties")
+            .argument(&t_offer_id.to_string()) //ID of the offer
+            .expect(TxExpect::ok().result(&format!("bech32:{}|biguint:{}", t_creator_address, t_royalties_amount)))
+        )
+        .sc_call( //Second call: take the offer
+            call_take_offer(&t_test_data,M_FEE).expect(expect_settlement(t_price, 0, t_royalties_amount, t_price - t_royalties_amount))
+        )
+        .check_state_step( //The creator got the royalties, the bidder got the rest of the price
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price - t_royalties_amount)))
+            .put_account(AddressKey::from(&bech32::decode(t_creator_address)), CheckAccount::new().balance(BigUintValue::from(t_royalties_amount)))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance("0").esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Create an offer for an NFT with 10% royalties bundled with one unit of a *
+* fungible token, and take it: the fungible token takes no share of the    *
+* price, so the creator still receives 10% of the whole price.             *
+*-------------------------------------------------------------------------*/
+#[test]
+fn take_bundle_offer_royalties_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_str_fungible_token_id : &str = "JUNK-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint price, and the royalties of the creator (1000 hundredths of a percent)
+    let t_price: u64 = 200000000000;
+    let t_royalties: u64 = 1000;
+    let t_royalties_amount: u64 = 20000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_creator_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_nft_all_properties(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""),t_royalties,Some(format!("bech32:{}", t_creator_address).as_str()),None,Vec::new()).esdt_balance(BytesKey::from(t_str_fungible_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)))
+    .put_account(AddressKey::from(&bech32::decode(t_creator_address)), Account::new().nonce(0));
+
+    // Create two instances of TxESDT, the NFT and the fungible token
+    let tx1 = TxESDT {
+        esdt_token_identifier: BytesValue::from(BytesKey::from(t_str_token_id.as_bytes().to_vec())),
+        nonce: U64Value::from(t_nonce),
+        esdt_value: BigUintValue::from(t_amount),
+    };
+
+    let tx2 = TxESDT {
+        esdt_token_identifier: BytesValue::from(BytesKey::from(t_str_fungible_token_id.as_bytes().to_vec())),
+        nonce: U64Value::from(0u64),
+        esdt_value: BigUintValue::from(t_amount),
+    };
+
+    // Create a vector and initialize it with tx1 and tx2
+    let tx_vec: Vec<TxESDT> = vec![tx1, tx2];
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: create the bundle offer
+            ScCallStep::new()
+            .from(AddressKey::from(&t_test_data.bidder_bech32)) //Address of the caller
+            .to("sc:exchangeplace") //destination of the call (the smart contract)
+            .multi_esdt_transfer(tx_vec) //Transfer the whole bundle
+            .function("createOffer") //The name of the function
+            .argument(BytesValue::from(t_test_data.price.to_be_bytes().as_ref())) //Price of the offer
+            .argument(BytesValue::from(t_test_data.taker_bech32.as_bytes())) //Address of the taker
+            .expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_query( //The view reports the royalties on the whole price
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getOfferRoyalties")
+            .argument(&t_offer_id.to_string()) //ID of the offer
+            .expect(TxExpect::ok().result(&format!("bech32:{}|biguint:{}", t_creator_address, t_royalties_amount)))
+        )
+        .sc_call( //Second call: take the offer
+            call_take_offer(&t_test_data,M_FEE).expect(expect_settlement(t_price, 0, t_royalties_amount, t_price - t_royalties_amount))
+        )
+        .check_state_step( //The creator got the royalties, the bidder got the rest of the price
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price - t_royalties_amount)))
+            .put_account(AddressKey::from(&bech32::decode(t_creator_address)), CheckAccount::new().balance(BigUintValue::from(t_royalties_amount)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Put an NFT with 10% royalties up for auction and settle it: the creator  *
+* receives the royalties out of the winning bid and the seller the rest.   *
+*-------------------------------------------------------------------------*/
+#[test]
+fn settle_auction_royalties_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint prices, and the royalties of the creator (1000 hundredths of a percent)
+    let t_reserve_price: u64 = 500000000000;
+    let t_min_increment: u64 = 100000000000;
+    let t_royalties: u64 = 1000;
+    let t_royalties_amount: u64 = 50000000000;
+    let t_auction_id: u64 = 1;
+
+    //Timestamps
+    let t_creation_timestamp: u64 = 500;
+    let t_end_timestamp: u64 = 1000;
+
+    //Addresses
+    let t_seller_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_winner_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_creator_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_reserve_price, t_auction_id, t_seller_address, t_winner_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_seller_address)), Account::new().nonce(0).esdt_nft_all_properties(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""),t_royalties,Some(format!("bech32:{}", t_creator_address).as_str()),None,Vec::new())) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_winner_address)), Account::new().nonce(0).balance(BigUintValue::from(t_reserve_price)))
+    .put_account(AddressKey::from(&bech32::decode(t_creator_address)), Account::new().nonce(0))
+    .block_timestamp(t_creation_timestamp);
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: put the NFT up for auction
+            call_create_auction(&t_test_data, t_min_increment, t_end_timestamp).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Second call: bid the reserve price
+            call_bid_on_auction(&t_test_data, &t_test_data.taker_bech32, t_reserve_price).expect(TxExpect::ok().no_result())
+        )
+        .set_state_step( //Let the auction end
+            SetStateStep::new().block_timestamp(t_end_timestamp)
+        )
+        .sc_call( //Third call: settle the auction
+            call_settle_auction(&t_test_data, &t_test_data.taker_bech32).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The creator got the royalties, the seller got the rest of the winning bid
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_reserve_price - t_royalties_amount)))
+            .put_account(AddressKey::from(&bech32::decode(t_creator_address)), CheckAccount::new().balance(BigUintValue::from(t_royalties_amount)))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance("0").esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+        );
+}
+/*-------------------------------------------------------------------------*
+* Fill a bid with an NFT with 10% royalties: the creator receives the      *
+* royalties out of the locked payment and the holder the rest.             *
+*-------------------------------------------------------------------------*/
+#[test]
+fn accept_bid_royalties_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 1;
+
+    //BigUint amount
+    let t_amount: u64 = 1;
+
+    //BigUint price, and the royalties of the creator (1000 hundredths of a percent)
+    let t_price: u64 = 200000000000;
+    let t_royalties: u64 = 1000;
+    let t_royalties_amount: u64 = 20000000000;
+    let t_bid_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_holder_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_creator_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_bid_id, t_bidder_address, t_holder_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price)))
+    .put_account(AddressKey::from(&bech32::decode(t_holder_address)), Account::new().nonce(0).esdt_nft_all_properties(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""),t_royalties,Some(format!("bech32:{}", t_creator_address).as_str()),None,Vec::new())) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_creator_address)), Account::new().nonce(0));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+         .sc_call( //First call: lock the price for the NFT
+            call_create_bid(&t_test_data).expect(expect_new_id(t_bid_id))
+         )
+        .sc_call( //Second call: the holder fills the bid
+            call_accept_bid(&t_test_data, t_nonce).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The creator got the royalties, the holder got the rest of the price
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance(BigUintValue::from(t_price - t_royalties_amount)))
+            .put_account(AddressKey::from(&bech32::decode(t_creator_address)), CheckAccount::new().balance(BigUintValue::from(t_royalties_amount)))
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance("0").esdt_nft_balance_and_attributes(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some("")))
+        );
+}
+/*-------------------------------------------------------------------------*
+* The owner sets a fee in basis points and a fee recipient. The taker pays *
+* the price plus the fee, the fee goes to the recipient.                   *
+*-------------------------------------------------------------------------*/
+#[test]
+fn take_offer_with_fee_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 200000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_recipient_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    //BigUint fee: 150 basis points (1.5%) of the price
+    let t_fee_basis_points: u64 = 150;
+    let t_fee: u64 = 3000000000;
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + t_fee)))
+    .put_account(AddressKey::from(&bech32::decode(t_recipient_address)), Account::new().nonce(0));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+        .sc_call( //First call: the owner sets the fee
+            call_set_fee(t_owner_address, t_fee_basis_points).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Second call: the owner sets the fee recipient
+            call_set_fee_recipient(t_owner_address, &bech32::decode(t_recipient_address)).expect(TxExpect::ok().no_result())
+        )
+        .sc_query( //The view reports the fee
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getFee")
+            .expect(TxExpect::ok().result(&format!("u64:{}|u8:0", t_fee_basis_points))) //Basis points, paid by the buyer
+        )
+         .sc_call( //Third call: create the offer
+            call_create_offer(&t_test_data, 0).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Fourth call: take the offer, paying the fee
+            call_take_offer(&t_test_data, t_fee).expect(expect_settlement(t_price + t_fee, t_fee, 0, t_price))
+        )
+        .sc_call( //Fifth call: the owner claims the fees
+            call_claim_fees(t_owner_address).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The bidder got the price, the recipient got the fee
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price)))
+            .put_account(AddressKey::from(&bech32::decode(t_recipient_address)), CheckAccount::new().balance(BigUintValue::from(t_fee)))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance("0").esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        );
+}
+/*-------------------------------------------------------------------------*
+* The owner sets a fee, and the taker pays the price without the fee.      *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Incorrect payment provided.")]
+fn take_offer_without_fee_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 200000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_recipient_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    //BigUint fee: 150 basis points (1.5%) of the price
+    let t_fee_basis_points: u64 = 150;
+    let t_fee: u64 = 3000000000;
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + t_fee)))
+    .put_account(AddressKey::from(&bech32::decode(t_recipient_address)), Account::new().nonce(0));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+        .sc_call( //First call: the owner sets the fee
+            call_set_fee(t_owner_address, t_fee_basis_points).expect(TxExpect::ok().no_result())
+        )
+         .sc_call( //Second call: create the offer
+            call_create_offer(&t_test_data, 0).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Third call: take the offer without the fee
+            call_take_offer(&t_test_data, 0)
+        );
 }
 /*-------------------------------------------------------------------------*
-* Calls the takeOffer function from the SC.                                *
+* Someone other than the owner tries to set the fee.                       *
 *-------------------------------------------------------------------------*/
-fn call_take_offer(p_test_data: &TestData, p_fee: u64) -> ScCallStep
-{
-    ScCallStep::new()
-    .from(AddressKey::from(&p_test_data.taker_bech32)) //AddressValue of the caller
-    .to("sc:exchangeplace") //destination of the call (the smart contract)
-    .egld_value(BigUintValue::from(p_test_data.price + p_fee))
-    .function("takeOffer") //The name of the function
-    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
-    .argument(BytesValue::from(p_test_data.bidder_bech32.as_bytes())) //Address of the bidder
+#[test]
+#[should_panic(expected = "Endpoint can only be called by owner")]
+fn set_fee_not_owner_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 200000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))); //Into bytes must be used in order for the VM to correctly parse the token ID string
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+        .sc_call( //First call: the bidder tries to set the fee
+            ScCallStep::new()
+            .from(AddressKey::from(&t_test_data.bidder_bech32)) //Address of the caller
+            .to("sc:exchangeplace") //Destination of the call (the smart contract)
+            .function("setFee") //The name of the function
+            .argument(BytesValue::from(t_price.to_be_bytes().as_ref())) //New fee
+        );
 }
 /*-------------------------------------------------------------------------*
-* Calls the refundOffer function from the SC.                              *
+* The owner sets a listing fee. The bidder pays it when creating an offer, *
+* and it goes to the owner while the rest of the transfer is listed.       *
 *-------------------------------------------------------------------------*/
-fn call_refund_offer(p_test_data: &TestData) -> ScCallStep
-{
-    ScCallStep::new()
-    .from(AddressKey::from(&p_test_data.bidder_bech32)) //AddressValue of the caller
-    .to("sc:exchangeplace") //Destination of the call (the smart contract)
-    .function("refundOffer") //The name of the function
-    .argument(BytesValue::from(p_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
-    .argument(BytesValue::from(p_test_data.taker_bech32.as_bytes())) //Address of the taker
+#[test]
+fn create_offer_with_listing_fee_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 200000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    //Listing fee
+    let t_str_fee_token_id : &str = "FEE-123456";
+    let t_listing_fee: u64 = 5000;
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)).esdt_balance(BytesKey::from(t_str_fee_token_id.as_bytes().to_vec()),BigUintValue::from(t_listing_fee))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price)));
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+        .sc_call( //First call: the owner sets the listing fee
+            call_set_listing_fee(t_owner_address, t_str_fee_token_id, t_listing_fee).expect(TxExpect::ok().no_result())
+        )
+         .sc_call( //Second call: create the offer, paying the listing fee
+            call_create_offer_with_listing_fee(&t_test_data, t_str_fee_token_id, t_listing_fee).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Third call: take the offer
+            call_take_offer(&t_test_data, 0).expect(expect_settlement(t_price, 0, 0, t_price))
+        )
+        .sc_call( //Fourth call: the owner claims the fees
+            call_claim_fees(t_owner_address).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The owner got the listing fee, the taker got the listed tokens only
+            CheckStateStep::new()
+            .put_account(t_owner_address, CheckAccount::new().esdt_balance(BytesKey::from(t_str_fee_token_id.as_bytes().to_vec()),BigUintValue::from(t_listing_fee)))
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price)).esdt_balance(BytesKey::from(t_str_fee_token_id.as_bytes().to_vec()),"0"))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance("0").esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        );
 }
 /*-------------------------------------------------------------------------*
-* Initialization test: deploy the contract.                                *
+* The owner sets a listing fee, and the bidder creates an offer without    *
+* paying it.                                                               *
 *-------------------------------------------------------------------------*/
 #[test]
-fn init_unit_test() {
-    let t_exchanger = barterswap::contract_obj::<SingleTxApi>();
-    t_exchanger.init();
+#[should_panic(expected = "Incorrect listing fee provided.")]
+fn create_offer_without_listing_fee_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
+
+    let t_owner_address : &str = "address:owner";
+
+    let t_sc_address : &str = "sc:exchangeplace";
+
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
+
+    let t_str_token_id : &str = "PROPO-123456";
+    let t_nonce: u64 = 0;
+
+    //BigUint amount
+    let t_amount: u64 = 100000000000;
+
+    //BigUint price
+    let t_price: u64 = 200000000000;
+    let t_offer_id: u64 = 1;
+
+    //Addresses
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    //Listing fee
+    let t_str_fee_token_id : &str = "FEE-123456";
+    let t_listing_fee: u64 = 5000;
+
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))); //Into bytes must be used in order for the VM to correctly parse the token ID string
+
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+        .sc_call( //First call: the owner sets the listing fee
+            call_set_listing_fee(t_owner_address, t_str_fee_token_id, t_listing_fee).expect(TxExpect::ok().no_result())
+        )
+         .sc_call( //Second call: create the offer without the listing fee
+            call_create_offer(&t_test_data, 0)
+         );
 }
 /*-------------------------------------------------------------------------*
-* Create an offer and then take it.                                        *
+* The owner sets a listing fee, and the bidder creates a barter offer      *
+* without paying it.                                                       *
 *-------------------------------------------------------------------------*/
 #[test]
-fn create_and_take_offer_unit_test() {
+#[should_panic(expected = "Incorrect listing fee provided.")]
+fn create_barter_offer_without_listing_fee_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -169,7 +4679,7 @@ fn create_and_take_offer_unit_test() {
     let t_amount: u64 = 100000000000;
 
     //BigUint price
-    let t_price: u64 = 700000000000;
+    let t_price: u64 = 200000000000;
     let t_offer_id: u64 = 1;
 
     //Addresses
@@ -178,11 +4688,14 @@ fn create_and_take_offer_unit_test() {
 
     let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
 
+    //Listing fee
+    let t_str_fee_token_id : &str = "FEE-123456";
+    let t_listing_fee: u64 = 5000;
+
     let t_set_step = SetStateStep::new()
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
     .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
-    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))); //Into bytes must be used in order for the VM to correctly parse the token ID string
 
     world.set_state_step(
             t_set_step
@@ -190,18 +4703,20 @@ fn create_and_take_offer_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            call_create_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
-         )
-        .sc_call(
-            call_take_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
-        );
+        .sc_call( //First call: the owner sets the listing fee
+            call_set_listing_fee(t_owner_address, t_str_fee_token_id, t_listing_fee).expect(TxExpect::ok().no_result())
+        )
+         .sc_call( //Second call: create the barter offer without the listing fee
+            call_create_barter_offer(&t_test_data, "USDC-123456", 0, t_price)
+         );
 }
 /*-------------------------------------------------------------------------*
-* Place an offer with a huge price.                                        *
+* The owner sets a listing fee, and the bidder creates a Dutch auction     *
+* offer without paying it.                                                 *
 *-------------------------------------------------------------------------*/
 #[test]
-fn create_offer_max_price_unit_test() {
+#[should_panic(expected = "Incorrect listing fee provided.")]
+fn create_dutch_offer_without_listing_fee_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -218,7 +4733,7 @@ fn create_offer_max_price_unit_test() {
     let t_amount: u64 = 100000000000;
 
     //BigUint price
-    let t_price: u64 = u64::MAX;
+    let t_price: u64 = 200000000000;
     let t_offer_id: u64 = 1;
 
     //Addresses
@@ -227,11 +4742,14 @@ fn create_offer_max_price_unit_test() {
 
     let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
 
+    //Listing fee
+    let t_str_fee_token_id : &str = "FEE-123456";
+    let t_listing_fee: u64 = 5000;
+
     let t_set_step = SetStateStep::new()
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
     .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
-    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))); //Into bytes must be used in order for the VM to correctly parse the token ID string
 
     world.set_state_step(
             t_set_step
@@ -239,15 +4757,20 @@ fn create_offer_max_price_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            call_create_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
+        .sc_call( //First call: the owner sets the listing fee
+            call_set_listing_fee(t_owner_address, t_str_fee_token_id, t_listing_fee).expect(TxExpect::ok().no_result())
+        )
+         .sc_call( //Second call: create the Dutch auction offer without the listing fee
+            call_create_dutch_offer(&t_test_data, t_price / 2, 1000, 2000)
          );
 }
 /*-------------------------------------------------------------------------*
-* Place an offer with a huge ID.                                           *
+* The owner sets a fee of 5% capped at 4000000000 EGLD, paid by the        *
+* seller. The taker pays the price only, the fee is taken from the         *
+* proceeds.                                                                *
 *-------------------------------------------------------------------------*/
 #[test]
-fn create_offer_max_id_unit_test() {
+fn take_offer_with_capped_seller_fee_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -264,8 +4787,8 @@ fn create_offer_max_id_unit_test() {
     let t_amount: u64 = 100000000000;
 
     //BigUint price
-    let t_price: u64 = 700000000000;
-    let t_offer_id: u64 = u64::MAX;
+    let t_price: u64 = 200000000000;
+    let t_offer_id: u64 = 1;
 
     //Addresses
     let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
@@ -273,11 +4796,15 @@ fn create_offer_max_id_unit_test() {
 
     let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
 
+    //BigUint fee: 500 basis points (5%) of the price would be 10000000000, above the maximum
+    let t_fee_basis_points: u64 = 500;
+    let t_max_fee: u64 = 4000000000;
+
     let t_set_step = SetStateStep::new()
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
     .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
     .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price)));
 
     world.set_state_step(
             t_set_step
@@ -285,15 +4812,37 @@ fn create_offer_max_id_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            call_create_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
-         );
+        .sc_call( //First call: the owner sets the fee
+            call_set_fee(t_owner_address, t_fee_basis_points).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Second call: the owner caps the fee paid in EGLD
+            call_set_fee_caps(t_owner_address, "EGLD", 0, t_max_fee).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: the owner makes the seller pay the fee
+            call_set_fee_paid_by_seller(t_owner_address, true).expect(TxExpect::ok().no_result())
+        )
+         .sc_call( //Fourth call: create the offer
+            call_create_offer(&t_test_data, 0).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Fifth call: take the offer, paying the price only
+            call_take_offer(&t_test_data, 0).expect(expect_settlement(t_price, t_max_fee, 0, t_price - t_max_fee))
+        )
+        .sc_call( //Sixth call: the owner claims the fees
+            call_claim_fees(t_owner_address).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The bidder got the price minus the capped fee, the owner got the fee
+            CheckStateStep::new()
+            .put_account(t_owner_address, CheckAccount::new().balance(BigUintValue::from(t_max_fee)))
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price - t_max_fee)))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance("0").esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount)))
+        );
 }
 /*-------------------------------------------------------------------------*
-* Place an offer with a huge ID.                                           *
+* The owner sets a fee of 0.01% with a minimum of 1000000000 EGLD. The     *
+* taker pays the price plus the minimum fee.                               *
 *-------------------------------------------------------------------------*/
 #[test]
-fn create_offer_max_nonce_unit_test() {
+fn take_offer_with_min_fee_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -304,13 +4853,13 @@ fn create_offer_max_nonce_unit_test() {
     let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
 
     let t_str_token_id : &str = "PROPO-123456";
-    let t_nonce: u64 = u64::MAX;
+    let t_nonce: u64 = 0;
 
     //BigUint amount
     let t_amount: u64 = 100000000000;
 
     //BigUint price
-    let t_price: u64 = 700000000000;
+    let t_price: u64 = 200000000000;
     let t_offer_id: u64 = 1;
 
     //Addresses
@@ -319,11 +4868,15 @@ fn create_offer_max_nonce_unit_test() {
 
     let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
 
+    //BigUint fee: 1 basis point (0.01%) of the price would be 20000000, below the minimum
+    let t_fee_basis_points: u64 = 1;
+    let t_min_fee: u64 = 1000000000;
+
     let t_set_step = SetStateStep::new()
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
     .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
-    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_nft_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),t_nonce,BigUintValue::from(t_amount),Some(""))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + t_min_fee)));
 
     world.set_state_step(
             t_set_step
@@ -331,16 +4884,34 @@ fn create_offer_max_nonce_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            call_create_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
-         );
+        .sc_call( //First call: the owner sets the fee
+            call_set_fee(t_owner_address, t_fee_basis_points).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Second call: the owner sets a minimum fee paid in EGLD
+            call_set_fee_caps(t_owner_address, "EGLD", t_min_fee, 0).expect(TxExpect::ok().no_result())
+        )
+         .sc_call( //Third call: create the offer
+            call_create_offer(&t_test_data, 0).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Fourth call: take the offer, paying the minimum fee
+            call_take_offer(&t_test_data, t_min_fee).expect(expect_settlement(t_price + t_min_fee, t_min_fee, 0, t_price))
+        )
+        .sc_call( //Fifth call: the owner claims the fees
+            call_claim_fees(t_owner_address).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The bidder got the price, the owner got the minimum fee
+            CheckStateStep::new()
+            .put_account(t_owner_address, CheckAccount::new().balance(BigUintValue::from(t_min_fee)))
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price)))
+        );
 }
 /*-------------------------------------------------------------------------*
-* Create two times the same offer (same id and taker address).             *
+* The owner sets a fee of 0.01% with a minimum of 1000000000 USDC. The     *
+* view reports the caps, and the taker of an offer priced in EGLD only     *
+* pays the basis points.                                                   *
 *-------------------------------------------------------------------------*/
 #[test]
-#[should_panic(expected = "Element already present. Try with different ID.")]
-fn create_offer_duplicated_unit_test() {
+fn take_offer_with_min_fee_of_other_token_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -350,29 +4921,33 @@ fn create_offer_duplicated_unit_test() {
     let mut world = world();
     let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
 
-    let t_str_token_id_1 : &str = "PROPO-123456";
-    let t_str_token_id_2 : &str = "PROPO-179101";
+    let t_str_token_id : &str = "PROPO-123456";
     let t_nonce: u64 = 0;
 
     //BigUint amount
     let t_amount: u64 = 100000000000;
 
     //BigUint price
-    let t_price: u64 = 700000000000;
+    let t_price: u64 = 200000000000;
     let t_offer_id: u64 = 1;
 
     //Addresses
     let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
     let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
 
-    let t_test_data_1 = TestData::new(t_str_token_id_1, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
-    let t_test_data_2 = TestData::new(t_str_token_id_2, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+
+    //BigUint fee: 1 basis point (0.01%) of the price, the minimum only applies to USDC
+    let t_fee_basis_points: u64 = 1;
+    let t_min_fee: u64 = 1000000000;
+    let t_fee: u64 = 20000000;
+    let t_fee_token : &str = "USDC-123456";
 
     let t_set_step = SetStateStep::new()
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
     .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
-    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id_1.as_bytes().to_vec()),BigUintValue::from(t_amount)).esdt_balance(BytesKey::from(t_str_token_id_2.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price)));        
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + t_fee)));
 
     world.set_state_step(
             t_set_step
@@ -380,20 +4955,41 @@ fn create_offer_duplicated_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            call_create_offer(&t_test_data_1,M_FEE).expect(TxExpect::ok().no_result())
+        .sc_call( //First call: the owner sets the fee
+            call_set_fee(t_owner_address, t_fee_basis_points).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Second call: the owner sets a minimum fee paid in USDC
+            call_set_fee_caps(t_owner_address, t_fee_token, t_min_fee, 0).expect(TxExpect::ok().no_result())
+        )
+        .sc_query( //The view reports the caps of USDC only
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getFeeCaps")
+            .expect(TxExpect::ok().result(&format!("str:{}", t_fee_token)).result(&t_min_fee.to_string()).result("0")) //Token, minimum, maximum
+        )
+         .sc_call( //Third call: create the offer
+            call_create_offer(&t_test_data, 0).expect(expect_new_id(t_test_data.offer_id))
          )
-        .sc_call(
-            call_create_offer(&t_test_data_2,M_FEE).expect(TxExpect::ok().no_result())
+        .sc_call( //Fourth call: take the offer, paying the basis points only
+            call_take_offer(&t_test_data, t_fee).expect(expect_settlement(t_price + t_fee, t_fee, 0, t_price))
+        )
+        .sc_call( //Fifth call: the owner claims the fees
+            call_claim_fees(t_owner_address).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The bidder got the price, the owner got the fee
+            CheckStateStep::new()
+            .put_account(t_owner_address, CheckAccount::new().balance(BigUintValue::from(t_fee)))
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price)))
         );
 }
 /*-------------------------------------------------------------------------*
-* The bidder places a bid, and the taker asks for a refund on an offer     *
-* that he didn't make (bad bidder address).                                *
+* The owner sets a fee of 1.5% and two discount tiers for holders of the   *
+* community token. The taker reaches the best tier and pays half the fee.  *
+* Ignored: the mock VM only reads the balance of the contract itself.      *
 *-------------------------------------------------------------------------*/
 #[test]
-#[should_panic(expected = "Refund offer not found.")]
-fn refund_offer_bad_address_unit_test() {
+#[ignore = "the mock VM cannot read the ESDT balance of other accounts"]
+fn take_offer_with_fee_discount_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -410,21 +5006,30 @@ fn refund_offer_bad_address_unit_test() {
     let t_amount: u64 = 100000000000;
 
     //BigUint price
-    let t_price: u64 = 700000000000;
+    let t_price: u64 = 200000000000;
     let t_offer_id: u64 = 1;
 
+    //BigUint fee: 150 basis points (1.5%) of the price, halved by the best tier
+    let t_fee_basis_points: u64 = 150;
+    let t_discounted_fee: u64 = 1500000000;
+
+    //Discount tiers of the community token: 20% from 1000 tokens, 50% from 5000 tokens
+    let t_str_community_token_id : &str = "COMM-123456";
+    let t_community_balance: u64 = 6000;
+    let t_best_discount: u64 = 5000;
+
     //Addresses
     let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
-    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    //The taker lives in the shard of the contract (shard 2 in the mock VM)
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcpq8evx2x";
 
     let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
-    let t_refund_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_taker_address, t_taker_address); //TAKER makes the call on an offer he didn't create: SHOULD PANIC
 
     let t_set_step = SetStateStep::new()
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
     .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
     .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0));        
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + t_discounted_fee)).esdt_balance(BytesKey::from(t_str_community_token_id.as_bytes().to_vec()),BigUintValue::from(t_community_balance)));
 
     world.set_state_step(
             t_set_step
@@ -432,20 +5037,47 @@ fn refund_offer_bad_address_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            call_create_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
+        .sc_call( //First call: the owner sets the fee
+            call_set_fee(t_owner_address, t_fee_basis_points).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Second call: the owner sets the discount token
+            call_set_discount_token(t_owner_address, t_str_community_token_id).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: the owner sets the first discount tier
+            call_set_discount_tier(t_owner_address, 1000, 2000).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Fourth call: the owner sets the second discount tier
+            call_set_discount_tier(t_owner_address, 5000, t_best_discount).expect(TxExpect::ok().no_result())
+        )
+        .sc_query( //The view reports the discount of the taker
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getFeeDiscount")
+            .argument(&format!("bech32:{}", t_taker_address)) //Address of the taker
+            .expect(TxExpect::ok().result(&t_best_discount.to_string()).result("true")) //Discount, same shard as the contract
+        )
+         .sc_call( //Fifth call: create the offer
+            call_create_offer(&t_test_data, 0).expect(expect_new_id(t_test_data.offer_id))
          )
-        .sc_call(
-            call_refund_offer(&t_refund_data)
+        .sc_call( //Sixth call: take the offer, paying the discounted fee
+            call_take_offer(&t_test_data, t_discounted_fee).expect(expect_settlement(t_price + t_discounted_fee, t_discounted_fee, 0, t_price))
+        )
+        .sc_call( //Seventh call: the owner claims the fees
+            call_claim_fees(t_owner_address).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The owner got the discounted fee, the taker kept the community tokens
+            CheckStateStep::new()
+            .put_account(t_owner_address, CheckAccount::new().balance(BigUintValue::from(t_discounted_fee)))
+            .put_account(AddressKey::from(&t_test_data.taker_bech32), CheckAccount::new().balance("0").esdt_balance(BytesKey::from(t_str_community_token_id.as_bytes().to_vec()),BigUintValue::from(t_community_balance)))
         );
 }
 /*-------------------------------------------------------------------------*
-* The bidder places a bid, and the taker asks for a refund on an offer     *
-* that doesn't exist (bad offer ID).                                       *
+* The owner sets the community token and two discount tiers, then removes  *
+* one of them with a discount of 0. An address in another shard than the   *
+* contract gets no discount, whatever its balance.                         *
 *-------------------------------------------------------------------------*/
 #[test]
-#[should_panic(expected = "Refund offer not found.")]
-fn refund_offer_bad_offerid_unit_test() {
+fn set_discount_tiers_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -455,28 +5087,17 @@ fn refund_offer_bad_offerid_unit_test() {
     let mut world = world();
     let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
 
-    let t_str_token_id : &str = "PROPO-123456";
-    let t_nonce: u64 = 0;
-
-    //BigUint amount
-    let t_amount: u64 = 100000000000;
-
-    //BigUint price
-    let t_price: u64 = 700000000000;
-    let t_offer_id: u64 = 1;
+    //Discount tiers of the community token: 20% from 1000 tokens, 50% from 5000 tokens
+    let t_str_community_token_id : &str = "COMM-123456";
+    let t_community_balance: u64 = 6000;
 
-    //Addresses
-    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
+    //Address in shard 0, the contract lives in shard 2 in the mock VM
     let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
 
-    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
-    let t_refund_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id + 1, t_bidder_address, t_taker_address); //Calls with offerid + 1, an offer that doesn't exist
-
     let t_set_step = SetStateStep::new()
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
     .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
-    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0));        
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_community_token_id.as_bytes().to_vec()),BigUintValue::from(t_community_balance)));
 
     world.set_state_step(
             t_set_step
@@ -484,20 +5105,44 @@ fn refund_offer_bad_offerid_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            call_create_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
-         )
-        .sc_call(
-            call_refund_offer(&t_refund_data)
+        .sc_call( //First call: the owner sets the discount token
+            call_set_discount_token(t_owner_address, t_str_community_token_id).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Second call: the owner sets the first discount tier
+            call_set_discount_tier(t_owner_address, 1000, 2000).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Third call: the owner sets the second discount tier
+            call_set_discount_tier(t_owner_address, 5000, 5000).expect(TxExpect::ok().no_result())
+        )
+        .sc_query( //The view reports both tiers
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getDiscountTiers")
+            .expect(TxExpect::ok().result("1000").result("2000").result("5000").result("5000")) //Minimum balance, discount of each tier
+        )
+        .sc_call( //Fourth call: the owner removes the second tier
+            call_set_discount_tier(t_owner_address, 5000, 0).expect(TxExpect::ok().no_result())
+        )
+        .sc_query( //The view reports the first tier only
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getDiscountTiers")
+            .expect(TxExpect::ok().result("1000").result("2000"))
+        )
+        .sc_query( //The address in another shard gets no discount
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getFeeDiscount")
+            .argument(&format!("bech32:{}", t_taker_address)) //Address of the taker
+            .expect(TxExpect::ok().result("0").result("false")) //Discount, same shard as the contract
         );
 }
 /*-------------------------------------------------------------------------*
-* The bidder places a bid, and the taker asks to take an offer with an     *
-* unexistant offer id.                                                     *
+* Two offers are taken with a fee. The fees stay in the contract until the *
+* owner claims them to another address.                                    *
 *-------------------------------------------------------------------------*/
 #[test]
-#[should_panic(expected = "Take offer not found.")]
-fn take_offer_bad_offerid_unit_test() {
+fn claim_accrued_fees_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -514,22 +5159,26 @@ fn take_offer_bad_offerid_unit_test() {
     let t_amount: u64 = 100000000000;
 
     //BigUint price
-    let t_price: u64 = 700000000000;
-    let t_offer_id: u64 = 1;
-    let t_offer_id_nonexistant: u64 = 2;
+    let t_price: u64 = 200000000000;
+
+    //BigUint fee: 150 basis points (1.5%) of the price
+    let t_fee_basis_points: u64 = 150;
+    let t_fee: u64 = 3000000000;
 
     //Addresses
     let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
     let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_treasury_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
 
-    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
-    let t_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id_nonexistant, t_bidder_address, t_taker_address); //ID of the offer (unexistant one, should panic)
+    let t_test_data_1 = TestData::new(t_str_token_id, t_nonce, t_amount / 2, t_price, 1, t_bidder_address, t_taker_address);
+    let t_test_data_2 = TestData::new(t_str_token_id, t_nonce, t_amount / 2, t_price, 2, t_bidder_address, t_taker_address);
 
     let t_set_step = SetStateStep::new()
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
     .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
     .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(2 * (t_price + t_fee))))
+    .put_account(AddressKey::from(&bech32::decode(t_treasury_address)), Account::new().nonce(0));
 
     world.set_state_step(
             t_set_step
@@ -537,19 +5186,53 @@ fn take_offer_bad_offerid_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            call_create_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
+        .sc_call( //First call: the owner sets the fee
+            call_set_fee(t_owner_address, t_fee_basis_points).expect(TxExpect::ok().no_result())
+        )
+         .sc_call( //Second call: create the first offer
+            call_create_offer(&t_test_data_1, 0).expect(expect_new_id(t_test_data_1.offer_id))
          )
-        .sc_call(
-            call_take_offer(&t_taker_data,M_FEE)
+         .sc_call( //Third call: create the second offer
+            call_create_offer(&t_test_data_2, 0).expect(expect_new_id(t_test_data_2.offer_id))
+         )
+        .sc_call( //Fourth call: take the first offer
+            call_take_offer(&t_test_data_1, t_fee).expect(expect_settlement(t_price + t_fee, t_fee, 0, t_price))
+        )
+        .sc_call( //Fifth call: take the second offer
+            call_take_offer(&t_test_data_2, t_fee).expect(expect_settlement(t_price + t_fee, t_fee, 0, t_price))
+        )
+        .check_state_step( //Both fees are kept by the contract
+            CheckStateStep::new()
+            .put_account(t_owner_address, CheckAccount::new().balance("0"))
+            .put_account(t_sc_address, CheckAccount::new().balance(BigUintValue::from(2 * t_fee)))
+        )
+        .sc_query( //The view reports the fees kept, in EGLD
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getAccruedFees")
+            .expect(TxExpect::ok().result("str:EGLD").result("0").result(&(2 * t_fee).to_string()))
+        )
+        .sc_call( //Sixth call: the owner claims the fees to the treasury
+            call_claim_fees_to(t_owner_address, &bech32::decode(t_treasury_address)).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The treasury got both fees
+            CheckStateStep::new()
+            .put_account(t_sc_address, CheckAccount::new().balance("0"))
+            .put_account(AddressKey::from(&bech32::decode(t_treasury_address)), CheckAccount::new().balance(BigUintValue::from(2 * t_fee)))
+        )
+        .sc_query( //Nothing is left to claim
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getAccruedFees")
+            .expect(TxExpect::ok().no_result())
         );
 }
 /*-------------------------------------------------------------------------*
-* Try to take an offer, but providing a bad bidder address.                *
+* The owner claims the fees before any fee was paid.                       *
 *-------------------------------------------------------------------------*/
 #[test]
-#[should_panic(expected = "Take offer not found.")]
-fn take_offer_bad_address_unit_test() {
+#[should_panic(expected = "No fees to claim.")]
+fn claim_no_fees_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -559,28 +5242,9 @@ fn take_offer_bad_address_unit_test() {
     let mut world = world();
     let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
 
-    let t_str_token_id : &str = "PROPO-123456";
-    let t_nonce: u64 = 0;
-
-    //BigUint amount
-    let t_amount: u64 = 100000000000;
-
-    //BigUint price
-    let t_price: u64 = 700000000000;
-    let t_offer_id: u64 = 1;
-
-    //Addresses
-    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
-    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
-
-    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
-    let t_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_taker_address, t_taker_address); //Provide taker's address as bidder. Generates error
-
     let t_set_step = SetStateStep::new()
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
-    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
-    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+    .new_address(t_owner_address, 1, t_sc_address); //define creator address expression (str), creator nonce (u64) and new address expression
 
     world.set_state_step(
             t_set_step
@@ -588,19 +5252,17 @@ fn take_offer_bad_address_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            call_create_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
-         )
-        .sc_call(
-            call_take_offer(&t_taker_data,M_FEE)
+        .sc_call( //First call: the owner claims the fees
+            call_claim_fees(t_owner_address)
         );
 }
 /*-------------------------------------------------------------------------*
-* Take an offer, but provided an incorrect payment in EGLD.                *
+* Two offers are taken with a fee, one in EGLD and one in an ESDT. The     *
+* owner first claims the fees in the ESDT only, and the fees in EGLD stay  *
+* in the contract until the next claim.                                    *
 *-------------------------------------------------------------------------*/
 #[test]
-#[should_panic(expected = "Incorrect payment provided.")]
-fn take_offer_invalid_payment_unit_test() {
+fn claim_fees_in_batches_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -611,27 +5273,31 @@ fn take_offer_invalid_payment_unit_test() {
     let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
 
     let t_str_token_id : &str = "PROPO-123456";
+    let t_str_payment_token_id : &str = "USDC-123456";
     let t_nonce: u64 = 0;
 
     //BigUint amount
     let t_amount: u64 = 100000000000;
 
     //BigUint price
-    let t_price: u64 = 700000000000;
-    let t_offer_id: u64 = 1;
+    let t_price: u64 = 200000000000;
+
+    //BigUint fee: 150 basis points (1.5%) of the price
+    let t_fee_basis_points: u64 = 150;
+    let t_fee: u64 = 3000000000;
 
     //Addresses
     let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
     let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
 
-    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
-    let t_taker_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price - 1, t_offer_id, t_bidder_address, t_taker_address); //Provide EGLD quantity - 1 (so not ENOUGH, PANIC)
+    let t_test_data_1 = TestData::new(t_str_token_id, t_nonce, t_amount / 2, t_price, 1, t_bidder_address, t_taker_address);
+    let t_test_data_2 = TestData::new(t_str_token_id, t_nonce, t_amount / 2, t_price, 2, t_bidder_address, t_taker_address);
 
     let t_set_step = SetStateStep::new()
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
     .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
     .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + t_fee)).esdt_balance(BytesKey::from(t_str_payment_token_id.as_bytes().to_vec()),BigUintValue::from(t_price + t_fee)));
 
     world.set_state_step(
             t_set_step
@@ -639,21 +5305,50 @@ fn take_offer_invalid_payment_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            call_create_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
+        .sc_call( //First call: the owner sets the fee
+            call_set_fee(t_owner_address, t_fee_basis_points).expect(TxExpect::ok().no_result())
+        )
+         .sc_call( //Second call: create the offer priced in EGLD
+            call_create_offer(&t_test_data_1, 0).expect(expect_new_id(t_test_data_1.offer_id))
          )
-        .sc_call(
-            call_take_offer(&t_taker_data,M_FEE)
+         .sc_call( //Third call: create the offer priced in the ESDT
+            call_create_offer_with_payment_token(&t_test_data_2, t_str_payment_token_id, 0).expect(expect_new_id(t_test_data_2.offer_id))
+         )
+        .sc_call( //Fourth call: take the offer priced in EGLD
+            call_take_offer(&t_test_data_1, t_fee).expect(expect_settlement(t_price + t_fee, t_fee, 0, t_price))
+        )
+        .sc_call( //Fifth call: take the offer priced in the ESDT
+            call_take_offer_with_esdt(&t_test_data_2, t_str_payment_token_id, 0, t_fee).expect(expect_settlement(t_price + t_fee, t_fee, 0, t_price))
+        )
+        .sc_call( //Sixth call: the owner claims the fees in the ESDT only
+            call_claim_fees_of(t_owner_address, &Address::zero(), &[(t_str_payment_token_id, 0)]).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The owner got the fees in the ESDT, the fees in EGLD are still kept
+            CheckStateStep::new()
+            .put_account(t_owner_address, CheckAccount::new().balance("0").esdt_balance(BytesKey::from(t_str_payment_token_id.as_bytes().to_vec()),BigUintValue::from(t_fee)))
+            .put_account(t_sc_address, CheckAccount::new().balance(BigUintValue::from(t_fee)))
+        )
+        .sc_query( //The view reports the fees left, in EGLD
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getAccruedFees")
+            .expect(TxExpect::ok().result("str:EGLD").result("0").result(&t_fee.to_string()))
+        )
+        .sc_call( //Seventh call: the owner claims the rest of the fees
+            call_claim_fees(t_owner_address).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //The owner got the fees in EGLD too
+            CheckStateStep::new()
+            .put_account(t_owner_address, CheckAccount::new().balance(BigUintValue::from(t_fee)))
         );
 }
 /*-------------------------------------------------------------------------*
-* Try to place an offer from a smart contract address (bidder). Normally   *
-* the contract is deployed as Non-payable by smart contract, so this is for*
-* pure testing.                                                            *
+* The owner approves a referrer with 20% of the fee. The offer is taken    *
+* through the referrer, who receives its share of the fee, and the rest of *
+* the fee stays in the contract.                                           *
 *-------------------------------------------------------------------------*/
 #[test]
-#[should_panic(expected = "Bidder address is from a smart contract.")]
-fn create_offer_bidder_sc_address_unit_test() {
+fn take_offer_with_referrer_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -670,21 +5365,28 @@ fn create_offer_bidder_sc_address_unit_test() {
     let t_amount: u64 = 100000000000;
 
     //BigUint price
-    let t_price: u64 = 700000000000;
+    let t_price: u64 = 200000000000;
     let t_offer_id: u64 = 1;
 
+    //BigUint fee: 150 basis points (1.5%) of the price, 20% of it for the referrer
+    let t_fee_basis_points: u64 = 150;
+    let t_fee: u64 = 3000000000;
+    let t_referrer_share: u64 = 2000;
+    let t_referral: u64 = 600000000;
+
     //Addresses
-    let t_bidder_address : &str = "erd1qqqqqqqqqqqqqpgq5cfxcvq5dqp290j2q9gw5yc8fcremmlqplkqtly3rs";
-    
+    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
     let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_referrer_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
 
     let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
 
     let t_set_step = SetStateStep::new()
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
     .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
-    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).code(EXCHANGE_PLACE_PATH_EXPR).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + t_fee)))
+    .put_account(AddressKey::from(&bech32::decode(t_referrer_address)), Account::new().nonce(0));
 
     world.set_state_step(
             t_set_step
@@ -692,18 +5394,39 @@ fn create_offer_bidder_sc_address_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            call_create_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
-         );
+        .sc_call( //First call: the owner sets the fee
+            call_set_fee(t_owner_address, t_fee_basis_points).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Second call: the owner approves the referrer
+            call_approve_referrer(t_owner_address, &bech32::decode(t_referrer_address), t_referrer_share).expect(TxExpect::ok().no_result())
+        )
+         .sc_call( //Third call: create the offer
+            call_create_offer(&t_test_data, 0).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Fourth call: take the offer through the referrer
+            call_take_offer_with_referrer(&t_test_data, t_fee, &bech32::decode(t_referrer_address)).expect(expect_settlement_with_referral(t_price + t_fee, t_fee, t_referral, 0, t_price))
+        )
+        .check_state_step( //The referrer got its share, the contract kept the rest of the fee
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&bech32::decode(t_referrer_address)), CheckAccount::new().balance(BigUintValue::from(t_referral)))
+            .put_account(t_sc_address, CheckAccount::new().balance(BigUintValue::from(t_fee - t_referral)))
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price)))
+        )
+        .sc_query( //The view reports the lifetime earnings of the referrer, in EGLD
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getReferrerEarnings")
+            .argument(&format!("bech32:{}", t_referrer_address)) //Address of the referrer
+            .expect(TxExpect::ok().result("str:EGLD").result("0").result(&t_referral.to_string()))
+        );
 }
 /*-------------------------------------------------------------------------*
-* Try to place an offer for a smart contract address (taker). Normally the *
-* contract is deployed as Non-payable by smart contract, so this is for    *
-* pure testing.                                                            *
+* The owner approves a referrer with a lifetime cap in EGLD below its      *
+* share of the fee. The view reports the cap, and the referrer only gets   *
+* the cap, the contract keeps the rest of the fee.                         *
 *-------------------------------------------------------------------------*/
 #[test]
-#[should_panic(expected = "Taker address is from a smart contract.")]
-fn create_offer_taker_sc_address_unit_test() {
+fn take_offer_with_capped_referrer_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -720,12 +5443,19 @@ fn create_offer_taker_sc_address_unit_test() {
     let t_amount: u64 = 100000000000;
 
     //BigUint price
-    let t_price: u64 = 700000000000;
+    let t_price: u64 = 200000000000;
     let t_offer_id: u64 = 1;
 
+    //BigUint fee: 150 basis points (1.5%) of the price, 20% of it for the referrer (600000000) but capped
+    let t_fee_basis_points: u64 = 150;
+    let t_fee: u64 = 3000000000;
+    let t_referrer_share: u64 = 2000;
+    let t_referral: u64 = 400000000;
+
     //Addresses
     let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
-    let t_taker_address : &str = "erd1qqqqqqqqqqqqqpgq5cfxcvq5dqp290j2q9gw5yc8fcremmlqplkqtly3rs";
+    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_referrer_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
 
     let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
 
@@ -733,7 +5463,8 @@ fn create_offer_taker_sc_address_unit_test() {
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
     .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
     .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).code(EXCHANGE_PLACE_PATH_EXPR).balance(BigUintValue::from(t_price + M_FEE)));        
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + t_fee)))
+    .put_account(AddressKey::from(&bech32::decode(t_referrer_address)), Account::new().nonce(0));
 
     world.set_state_step(
             t_set_step
@@ -741,16 +5472,44 @@ fn create_offer_taker_sc_address_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            call_create_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
-         );
+        .sc_call( //First call: the owner sets the fee
+            call_set_fee(t_owner_address, t_fee_basis_points).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Second call: the owner approves the referrer
+            call_approve_referrer_with_cap(t_owner_address, &bech32::decode(t_referrer_address), t_referrer_share, "EGLD", t_referral).expect(TxExpect::ok().no_result())
+        )
+        .sc_query( //The view reports the share and the cap of the referrer
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getReferrers")
+            .expect(TxExpect::ok().result(&format!("bech32:{}", t_referrer_address)).result(&format!("u64:{}|u32:1|u32:4|str:EGLD|u64:0|biguint:{}", t_referrer_share, t_referral))) //Address, share, caps (token, nonce, amount)
+        )
+         .sc_call( //Third call: create the offer
+            call_create_offer(&t_test_data, 0).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Fourth call: take the offer through the referrer
+            call_take_offer_with_referrer(&t_test_data, t_fee, &bech32::decode(t_referrer_address)).expect(expect_settlement_with_referral(t_price + t_fee, t_fee, t_referral, 0, t_price))
+        )
+        .check_state_step( //The referrer got its cap, the contract kept the rest of the fee
+            CheckStateStep::new()
+            .put_account(AddressKey::from(&bech32::decode(t_referrer_address)), CheckAccount::new().balance(BigUintValue::from(t_referral)))
+            .put_account(t_sc_address, CheckAccount::new().balance(BigUintValue::from(t_fee - t_referral)))
+            .put_account(AddressKey::from(&t_test_data.bidder_bech32), CheckAccount::new().balance(BigUintValue::from(t_price)))
+        )
+        .sc_query( //The view reports the lifetime earnings of the referrer, in EGLD
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getReferrerEarnings")
+            .argument(&format!("bech32:{}", t_referrer_address)) //Address of the referrer
+            .expect(TxExpect::ok().result("str:EGLD").result("0").result(&t_referral.to_string()))
+        );
 }
 /*-------------------------------------------------------------------------*
-* Create an offer and then take it.                                        *
+* Take an offer through a referrer the owner never approved.               *
 *-------------------------------------------------------------------------*/
 #[test]
-#[should_panic(expected = "incorrect number of ESDT transfers")]
-fn create_and_take_offer_no_nft_unit_test() {
+#[should_panic(expected = "Referrer not approved.")]
+fn take_offer_with_unapproved_referrer_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -767,12 +5526,17 @@ fn create_and_take_offer_no_nft_unit_test() {
     let t_amount: u64 = 100000000000;
 
     //BigUint price
-    let t_price: u64 = 700000000000;
+    let t_price: u64 = 200000000000;
     let t_offer_id: u64 = 1;
 
+    //BigUint fee: 150 basis points (1.5%) of the price
+    let t_fee_basis_points: u64 = 150;
+    let t_fee: u64 = 3000000000;
+
     //Addresses
     let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
     let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    let t_referrer_address : &str = "erd1fggp5ru0jhcjrp5rjqyqrnvhr3sz3v2e0fm3ktknvlg7mcyan54qzccnan";
 
     let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
 
@@ -780,7 +5544,8 @@ fn create_and_take_offer_no_nft_unit_test() {
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
     .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
     .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + t_fee)))
+    .put_account(AddressKey::from(&bech32::decode(t_referrer_address)), Account::new().nonce(0));
 
     world.set_state_step(
             t_set_step
@@ -788,27 +5553,23 @@ fn create_and_take_offer_no_nft_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            ScCallStep::new()
-                .from(AddressKey::from(&t_test_data.bidder_bech32)) //Address of the caller
-                .to("sc:exchangeplace") //destination of the call (the smart contract)
-                //.esdt_transfer(BytesKey::from(t_test_data.str_token_id.clone().into_bytes()),t_test_data.nonce,BigUintValue::from(t_test_data.amount)) //Don't transfer the NFT or SFT
-                .egld_value(BigUintValue::from(M_FEE)) //Pay the fee
-                .function("createOffer") //The name of the function
-                .argument(BytesValue::from(t_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
-                .argument(BytesValue::from(t_test_data.price.to_be_bytes().as_ref())) //Price of the offer
-                .argument(BytesValue::from(t_test_data.taker_bech32.as_bytes())) //Address of the taker.expect(TxExpect::ok().no_result())
-            )
-        .sc_call(
-            call_take_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
+        .sc_call( //First call: the owner sets the fee
+            call_set_fee(t_owner_address, t_fee_basis_points).expect(TxExpect::ok().no_result())
+        )
+         .sc_call( //Second call: create the offer
+            call_create_offer(&t_test_data, 0).expect(expect_new_id(t_test_data.offer_id))
+         )
+        .sc_call( //Third call: take the offer through the referrer
+            call_take_offer_with_referrer(&t_test_data, t_fee, &bech32::decode(t_referrer_address))
         );
 }
 /*-------------------------------------------------------------------------*
-* Simultaneous call for taking and refunding an offer.                     *
+* The owner splits the fees among a treasury (50%), a dev fund (30%) and   *
+* a community pool (20%). An offer is taken with a fee, and the claimed    *
+* fee is split by those shares.                                            *
 *-------------------------------------------------------------------------*/
 #[test]
-#[should_panic(expected = "Refund offer not found.")]
-fn create_and_take_and_refund_offer_unit_test() {
+fn claim_fees_to_beneficiaries_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -825,9 +5586,16 @@ fn create_and_take_and_refund_offer_unit_test() {
     let t_amount: u64 = 100000000000;
 
     //BigUint price
-    let t_price: u64 = 700000000000;
+    let t_price: u64 = 200000000000;
     let t_offer_id: u64 = 1;
 
+    //BigUint fee: 150 basis points (1.5%) of the price
+    let t_fee_basis_points: u64 = 150;
+    let t_fee: u64 = 3000000000;
+
+    //Beneficiaries of the fees and their shares
+    let t_beneficiaries: [(&str, u64); 3] = [("address:treasury", 5000), ("address:devfund", 3000), ("address:community", 2000)];
+
     //Addresses
     let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
     let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
@@ -838,7 +5606,10 @@ fn create_and_take_and_refund_offer_unit_test() {
     .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
     .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
     .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + t_fee)))
+    .put_account(t_beneficiaries[0].0, Account::new().nonce(0))
+    .put_account(t_beneficiaries[1].0, Account::new().nonce(0))
+    .put_account(t_beneficiaries[2].0, Account::new().nonce(0));
 
     world.set_state_step(
             t_set_step
@@ -846,22 +5617,42 @@ fn create_and_take_and_refund_offer_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            call_create_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
+        .sc_call( //First call: the owner sets the fee
+            call_set_fee(t_owner_address, t_fee_basis_points).expect(TxExpect::ok().no_result())
+        )
+        .sc_call( //Second call: the owner sets the beneficiaries
+            call_set_fee_beneficiaries(t_owner_address, &t_beneficiaries).expect(TxExpect::ok().no_result())
+        )
+        .sc_query( //The view reports the beneficiaries and their shares
+            ScQueryStep::new()
+            .to("sc:exchangeplace")
+            .function("getFeeBeneficiaries")
+            .expect(TxExpect::ok().result("address:treasury").result("5000").result("address:devfund").result("3000").result("address:community").result("2000"))
+        )
+         .sc_call( //Third call: create the offer
+            call_create_offer(&t_test_data, 0).expect(expect_new_id(t_test_data.offer_id))
          )
-        .sc_call(
-            call_take_offer(&t_test_data,M_FEE).expect(TxExpect::ok().no_result())
+        .sc_call( //Fourth call: take the offer, paying the fee
+            call_take_offer(&t_test_data, t_fee).expect(expect_settlement(t_price + t_fee, t_fee, 0, t_price))
         )
-        .sc_call(
-            call_refund_offer(&t_test_data).expect(TxExpect::ok().no_result())
+        .sc_call( //Fifth call: the owner claims the fees
+            call_claim_fees(t_owner_address).expect(TxExpect::ok().no_result())
+        )
+        .check_state_step( //Each beneficiary got its share of the fee
+            CheckStateStep::new()
+            .put_account(t_sc_address, CheckAccount::new().balance("0"))
+            .put_account(t_owner_address, CheckAccount::new().balance("0"))
+            .put_account(t_beneficiaries[0].0, CheckAccount::new().balance(BigUintValue::from(t_fee / 2)))
+            .put_account(t_beneficiaries[1].0, CheckAccount::new().balance(BigUintValue::from(t_fee * 3 / 10)))
+            .put_account(t_beneficiaries[2].0, CheckAccount::new().balance(BigUintValue::from(t_fee / 5)))
         );
 }
 /*-------------------------------------------------------------------------*
-* Forbidden multi_esdt transfer on create.                                 *
+* The owner sets beneficiaries whose shares add up to 90% only.            *
 *-------------------------------------------------------------------------*/
 #[test]
-#[should_panic(expected = "incorrect number of ESDT transfers")]
-fn create_multi_esdt_offer_unit_test() {
+#[should_panic(expected = "Beneficiary shares must add up to 10000 basis points.")]
+fn set_fee_beneficiaries_bad_shares_unit_test() {
     //std::env::set_var("RUST_BACKTRACE", "full");
 
     let t_owner_address : &str = "address:owner";
@@ -871,43 +5662,44 @@ fn create_multi_esdt_offer_unit_test() {
     let mut world = world();
     let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
 
-    let t_str_token_id : &str = "PROPO-123456";
-    let t_nonce: u64 = 0;
-
-    //BigUint amount
-    let t_amount: u64 = 100000000000;
+    //Beneficiaries of the fees and their shares
+    let t_beneficiaries: [(&str, u64); 3] = [("address:treasury", 5000), ("address:devfund", 3000), ("address:community", 1000)];
 
-    //BigUint price
-    let t_price: u64 = 700000000000;
-    let t_offer_id: u64 = 1;
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address); //define creator address expression (str), creator nonce (u64) and new address expression
 
-    //Addresses
-    let t_bidder_address : &str = "erd1suej7d7yl5x95quuh38ur9x0vj2tdvy3rzuqx9n4dnulskyxvl0q0ec3n0";
-    let t_taker_address : &str = "erd16jruked88jgtsar78ej85hjp3qsd9jkjcw4swsn7k0teqh3wgcqqgyrupq";
+    world.set_state_step(
+            t_set_step
+        )
+        .sc_deploy( //deploy a step
+            deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
+        )
+        .sc_call( //First call: the owner sets the beneficiaries
+            call_set_fee_beneficiaries(t_owner_address, &t_beneficiaries)
+        );
+}
+/*-------------------------------------------------------------------------*
+* The owner sets a share so large the total wraps around to 10000.         *
+*-------------------------------------------------------------------------*/
+#[test]
+#[should_panic(expected = "Invalid beneficiary provided.")]
+fn set_fee_beneficiaries_overflowing_share_unit_test() {
+    //std::env::set_var("RUST_BACKTRACE", "full");
 
-    let t_test_data = TestData::new(t_str_token_id, t_nonce, t_amount, t_price, t_offer_id, t_bidder_address, t_taker_address);
+    let t_owner_address : &str = "address:owner";
 
-    let t_set_step = SetStateStep::new()
-    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
-    .new_address(t_owner_address, 1, t_sc_address) //define creator address expression (str), creator nonce (u64) and new address expression
-    .put_account(AddressKey::from(&bech32::decode(t_bidder_address)), Account::new().nonce(0).esdt_balance(BytesKey::from(t_str_token_id.as_bytes().to_vec()),BigUintValue::from(2*t_amount))) //Into bytes must be used in order for the VM to correctly parse the token ID string
-    .put_account(AddressKey::from(&bech32::decode(t_taker_address)), Account::new().nonce(0).balance(BigUintValue::from(t_price + M_FEE)));        
+    let t_sc_address : &str = "sc:exchangeplace";
 
-    // Create two instances of TxESDT
-    let tx1 = TxESDT {
-        esdt_token_identifier: BytesValue::from(BytesKey::from(t_test_data.str_token_id.clone().into_bytes())),
-        nonce: U64Value::from(t_test_data.nonce),
-        esdt_value: BigUintValue::from(BigUintValue::from(t_test_data.amount)),
-    };
+    let mut world = world();
+    let exchange_place_code = world.code_expression(EXCHANGE_PLACE_PATH_EXPR); //BytesValue representing the wasm code
 
-    let tx2 = TxESDT {
-        esdt_token_identifier: BytesValue::from(BytesKey::from(t_test_data.str_token_id.clone().into_bytes())),
-        nonce: U64Value::from(t_test_data.nonce),
-        esdt_value: BigUintValue::from(BigUintValue::from(t_test_data.amount)),
-    };
+    //Beneficiaries of the fees and their shares, the second one overflowing the total
+    let t_beneficiaries: [(&str, u64); 2] = [("address:treasury", 10001), ("address:devfund", u64::MAX)];
 
-    // Create a vector and initialize it with tx1 and tx2
-    let tx_vec: Vec<TxESDT> = vec![tx1, tx2];
+    let t_set_step = SetStateStep::new()
+    .put_account(t_owner_address, Account::new().nonce(1)) //define address expression (str) and Account struct
+    .new_address(t_owner_address, 1, t_sc_address); //define creator address expression (str), creator nonce (u64) and new address expression
 
     world.set_state_step(
             t_set_step
@@ -915,15 +5707,7 @@ fn create_multi_esdt_offer_unit_test() {
         .sc_deploy( //deploy a step
             deploy_step(t_owner_address, &exchange_place_code).expect(TxExpect::ok().no_result()) //expect a TxExpect struct
         )
-         .sc_call( //First call: place the bid
-            ScCallStep::new()
-            .from(AddressKey::from(&t_test_data.bidder_bech32)) //Address of the caller
-            .to("sc:exchangeplace") //destination of the call (the smart contract)
-            .multi_esdt_transfer(tx_vec) //Transfer the NFT or SFT
-            .egld_value(BigUintValue::from(M_FEE)) //Pay the fee
-            .function("createOffer") //The name of the function
-            .argument(BytesValue::from(t_test_data.offer_id.to_be_bytes().as_ref())) //ID of the offer
-            .argument(BytesValue::from(t_test_data.price.to_be_bytes().as_ref())) //Price of the offer
-            .argument(BytesValue::from(t_test_data.taker_bech32.as_bytes())) //Address of the taker
-         );
+        .sc_call( //First call: the owner sets the beneficiaries
+            call_set_fee_beneficiaries(t_owner_address, &t_beneficiaries)
+        );
 }
@@ -5,9 +5,9 @@
 ////////////////////////////////////////////////////
 
 // Init:                                 1
-// Endpoints:                            4
+// Endpoints:                            58
 // Async Callback (empty):               1
-// Total number of exported functions:   6
+// Total number of exported functions:   60
 
 #![no_std]
 
@@ -23,9 +23,63 @@ multiversx_sc_wasm_adapter::endpoints! {
     (
         init => init
         createOffer => create_offer
+        createBarterOffer => create_barter_offer
+        createDutchOffer => create_dutch_offer
         refundOffer => refund_offer
+        reclaimExpiredOffer => reclaim_expired_offer
         takeOffer => take_offer
-        getMarketplaceElements => marketplace_elements
+        updateOfferPrice => update_offer_price
+        addToOffer => add_to_offer
+        withdrawFromOffer => withdraw_from_offer
+        changeOfferTaker => change_offer_taker
+        proposeCounter => propose_counter
+        acceptCounter => accept_counter
+        rejectCounter => reject_counter
+        createBid => create_bid
+        createCollectionBid => create_collection_bid
+        acceptBid => accept_bid
+        cancelBid => cancel_bid
+        createAuction => create_auction
+        bidOnAuction => bid_on_auction
+        settleAuction => settle_auction
+        createSealedAuction => create_sealed_auction
+        commitSealedBid => commit_sealed_bid
+        revealSealedBid => reveal_sealed_bid
+        settleSealedAuction => settle_sealed_auction
+        withdrawSealedDeposit => withdraw_sealed_deposit
+        createRing => create_ring
+        fundRing => fund_ring
+        cancelRing => cancel_ring
+        setFee => set_fee
+        setFeeCaps => set_fee_caps
+        setFeePaidBySeller => set_fee_paid_by_seller
+        setListingFee => set_listing_fee
+        setFeeRecipient => set_fee_recipient
+        approveReferrer => approve_referrer
+        revokeReferrer => revoke_referrer
+        setFeeBeneficiaries => set_fee_beneficiaries
+        claimFees => claim_fees
+        setDiscountToken => set_discount_token
+        setDiscountTier => set_discount_tier
+        getMarketplaceElements => get_marketplace_elements
+        getFee => get_fee
+        getFeeCaps => get_fee_caps
+        getListingFee => get_listing_fee
+        getAccruedFees => get_accrued_fees
+        getReferrers => get_referrers
+        getReferrerEarnings => get_referrer_earnings
+        getFeeBeneficiaries => get_fee_beneficiaries
+        getFeeRecipient => get_fee_recipient
+        getDiscountTiers => get_discount_tiers
+        getFeeDiscount => get_fee_discount
+        getCurrentPrice => get_current_price
+        getOfferRoyalties => get_offer_royalties
+        getNegotiationHistory => get_negotiation_history
+        getBids => get_bids
+        getAuctions => get_auctions
+        getSealedAuctions => get_sealed_auctions
+        getRing => get_ring
+        computeBidHash => compute_bid_hash
     )
 }
 